@@ -1,14 +1,23 @@
 // ©2025 - BestJasmine - BestMat - All rights reserved.
 
 #[path="./types.rs"] pub mod types;
+#[path="./printer.rs"] pub mod printer;
+#[path="./error.rs"] pub mod error;
+#[cfg(test)] #[path="./tests.rs"] mod tests;
 
+use std::cell::{Cell, RefCell};
 use std::fmt;
 use std::fmt::{Display, Formatter};
+use std::fs;
 use std::fs::File;
+use std::io;
 use std::io::Write;
-use crate::codegen::types::CType;
-use std::sync::Once;
-use std::mem::MaybeUninit;
+use std::path::Path;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use crate::codegen::error::{JasmineError, JasmineResult};
+use crate::codegen::printer::{CBlockKind, CPrinter};
+use crate::codegen::types::{CArraySize, CType};
 
 // const C_LIBS: [CLibrary; 2] = [
 //     CLibrary {
@@ -22,15 +31,82 @@ use std::mem::MaybeUninit;
 // ];
 
 
-static mut CHAR_PTR_TYPE: MaybeUninit<CType> = MaybeUninit::uninit();
-static INIT_CHAR_PTR: Once = Once::new();
+/* Escapes text into the body of a C string or character literal.
+   A hex escape swallows every hex digit after it, so the string literal is split ("\xC3\xA9" "a") when one follows */
+fn escape_literal(text: &str, quote: char, prefix: CStringPrefix) -> String {
+    let mut escaped = String::new();
+    let mut previous = None;
+    let mut after_hex_escape = false;
 
-const INDENT: &str = "    ";
+    for char in text.chars() {
+        if after_hex_escape && char.is_ascii_hexdigit() {
+            escaped.push_str(&format!("{quote} {quote}"));
+        }
+        after_hex_escape = false;
+
+        match char {
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            '\u{7}' => escaped.push_str("\\a"),
+            '\u{8}' => escaped.push_str("\\b"),
+            '\u{b}' => escaped.push_str("\\v"),
+            '\u{c}' => escaped.push_str("\\f"),
+            '\\' => escaped.push_str("\\\\"),
+            '"' | '\'' if char == quote => escaped.push_str(&format!("\\{}", char)),
+
+            // ??= and friends are trigraphs before C23:
+            '?' if previous == Some('?') => escaped.push_str("\\?"),
+
+            // Octal escapes stop after three digits, so they never swallow the next character:
+            char if char.is_ascii_control() => escaped.push_str(&format!("\\{:03o}", char as u32)),
+            char if char.is_ascii() => escaped.push(char),
+
+            // Narrow and u8 literals spell out their UTF-8 bytes, wide literals use universal character names:
+            char => match prefix {
+                CStringPrefix::None | CStringPrefix::Utf8 => {
+                    let mut bytes = [0; 4];
+                    for byte in char.encode_utf8(&mut bytes).bytes() {
+                        escaped.push_str(&format!("\\x{:02X}", byte));
+                    }
+                    after_hex_escape = true;
+                }
+                _ if (char as u32) < 0xA0 => {
+                    escaped.push_str(&format!("\\x{:X}", char as u32));
+                    after_hex_escape = true;
+                }
+                _ if (char as u32) <= 0xFFFF => escaped.push_str(&format!("\\u{:04X}", char as u32)),
+                _ => escaped.push_str(&format!("\\U{:08X}", char as u32)),
+            },
+        }
+
+        previous = Some(char);
+    }
+
+    escaped
+}
+
+/* Revision of the C standard that the generated code targets */
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum CStandard {
+    C89,
+    C99,
+    C11,
+    C17,
+    C23,
+}
 
 #[derive(Debug, Clone)]
 pub struct Codegen {
     pub c_file: CFile,                  // C File
     pub c_program: CProgramNode,        // C Program
+    pub check_casts: bool,              // Warn about lossy or suspicious casts
+    pub printer: CPrinter,              // Layout of the generated code
+    pub standard: CStandard,            // C standard of the generated code
+    loop_depth: Cell<usize>,            // Number of enclosing loops while generating
+    switch_depth: Cell<usize>,          // Number of enclosing switches while generating
+    used_headers: RefCell<Vec<&'static str>>,// Standard headers needed by the types generated so far
+    warnings: RefCell<Vec<String>>,     // Lossy or suspicious casts found by the last render
 }
 #[derive(Debug, Clone)]
 pub struct CFile {
@@ -58,6 +134,7 @@ pub struct CLibrary {
 
 /* TODO: Implement `Display` for CProgramNode and its children */
 #[derive(Debug, Clone)]
+#[allow(clippy::enum_variant_names, clippy::large_enum_variant)]
 pub enum CProgramNode {
     CStatement(CStatement),             // C Statements
     CExpression(CExpression),           // C Expressions
@@ -72,33 +149,110 @@ pub enum CStatement {
     ReturnStatement(CReturnStatement),// return x;
     FunctionCallStatement(CFunctionCallStatement),// printf("Hello, world!\n");
     BreakStatement,                     // break;
+    ContinueStatement,                  // continue;
+    GotoStatement(String),              // goto cleanup;
+    LabelStatement(String),             // cleanup:
 
     /* Block Statements */
     BlockStatement(CBlockStatement),    // {}
-    IfStatement,                        // if (x == 27) {}
-    WhileStatement,                     // while (1) {}
-    ForStatement,                       // for (int i = 0; i < 27; i++) {}
-    DoWhileStatement,                   // do {} while (x == 21)
+    IfStatement(CIfStatement),          // if (x == 27) {} else if (x == 21) {} else {}
+    WhileStatement(CWhileStatement),    // while (1) {}
+    ForStatement(CForStatement),        // for (int i = 0; i < 27; i++) {}
+    DoWhileStatement(CDoWhileStatement),// do {} while (x == 21);
+    SwitchStatement(CSwitchStatement),  // switch (x) { case 1: break; default: break; }
     FunctionStatement(CFunctionStatement),// int main() {}
 
+    /* Type Definitions */
+    StructDefinition(CStructDefinition),// struct Point { int x; int y; };
+    UnionDefinition(CUnionDefinition),  // union Value { int i; float f; };
+    EnumDefinition(CEnumDefinition),    // enum Color { RED, GREEN = 5 };
+
     /* Header Statements */
     IncludeStatement(CIncludeStatement),// #include <stdio.h>
-    DefineStatement,                    // #define PI 3.14
-    PragmaStatement,                    // #pragma
-    TypedefStatement,                   // typedef int JavaScript_Number;
+    DefineStatement(CDefineStatement),  // #define PI 3.14, #define MAX(a, b) ((a) > (b) ? (a) : (b))
+    UndefStatement(String),             // #undef PI
+    ConditionalStatement(CConditionalStatement),// #ifdef DEBUG ... #elif ... #else ... #endif
+    PragmaStatement(CPragma),           // #pragma once
+    TypedefStatement(CTypedefStatement),// typedef int JavaScript_Number;
 }
 
 #[derive(Debug, Clone)]
 pub struct CVariableStatement {
     pub var_type: CType,
     pub var_name: String,
-    pub var_value: CExpression,
+    pub var_value: Option<CExpression>, // None declares the variable without an initializer (int x;)
+    pub storage_class: Option<CStorageClass>,// static int counter = 0;
+    pub thread_local: bool,             // _Thread_local (C11+), alone at file scope or with static/extern
+    pub declarators: Vec<CDeclarator>,  // Further variables of the same type (int x = 1, y, z = 3;)
+}
+
+impl CVariableStatement {
+    pub fn new(var_type: CType, var_name: &str, var_value: CExpression) -> Self {
+        Self {
+            var_type,
+            var_name: var_name.to_string(),
+            var_value: Some(var_value),
+            storage_class: None,
+            thread_local: false,
+            declarators: Vec::new(),
+        }
+    }
+
+    pub fn uninitialized(var_type: CType, var_name: &str) -> Self {
+        Self { var_value: None, ..Self::new(var_type, var_name, CExpression::NumberExpression(CNumberExpression::new(0))) }
+    }
+
+    /* Declares one more variable of the same type in the statement */
+    pub fn with_declarator(mut self, name: &str, value: Option<CExpression>) -> Self {
+        self.declarators.push(CDeclarator { name: name.to_string(), value });
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CDeclarator {
+    pub name: String,
+    pub value: Option<CExpression>,
 }
 
 #[derive(Debug, Clone)]
 pub struct CAssignmentStatement {
-    pub var_name: String,
-    pub var_value: CExpression,
+    pub target: CExpression,            // Must be an lvalue (x, *p, p->x, arr[i])
+    pub operator: CAssignmentOperator,
+    pub value: CExpression,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CAssignmentOperator {
+    Assign,                             // =
+    AddAssign,                          // +=
+    SubtractAssign,                     // -=
+    MultiplyAssign,                     // *=
+    DivideAssign,                       // /=
+    ModuloAssign,                       // %=
+    BitwiseAndAssign,                   // &=
+    BitwiseOrAssign,                    // |=
+    BitwiseXorAssign,                   // ^=
+    ShiftLeftAssign,                    // <<=
+    ShiftRightAssign,                   // >>=
+}
+
+impl Display for CAssignmentOperator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CAssignmentOperator::Assign => write!(f, "="),
+            CAssignmentOperator::AddAssign => write!(f, "+="),
+            CAssignmentOperator::SubtractAssign => write!(f, "-="),
+            CAssignmentOperator::MultiplyAssign => write!(f, "*="),
+            CAssignmentOperator::DivideAssign => write!(f, "/="),
+            CAssignmentOperator::ModuloAssign => write!(f, "%="),
+            CAssignmentOperator::BitwiseAndAssign => write!(f, "&="),
+            CAssignmentOperator::BitwiseOrAssign => write!(f, "|="),
+            CAssignmentOperator::BitwiseXorAssign => write!(f, "^="),
+            CAssignmentOperator::ShiftLeftAssign => write!(f, "<<="),
+            CAssignmentOperator::ShiftRightAssign => write!(f, ">>="),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -106,122 +260,713 @@ pub struct CReturnStatement {
     pub value: CExpression,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::enum_variant_names)]
 pub enum CExpression {
     /* Basic Expressions */
-    NumberExpression(CNumberExpression),// 21
-    StringExpression(CStringExpression),// "Hello, world!\n"
+    NumberExpression(CNumberExpression),// 21, 0xCAFEBABEU, 0123, 21ULL
+    StringExpression(CStringExpression),// "Hello, world!\n", L"Grüße"
     IdentifierExpression(CIdentifierExpression),// x
     DecimalExpression(CDecimalExpression),// 3.14159
-    ArrayExpression(CArrayExpression),// [1, 2, 3]
-    CharExpression(CCharExpression),// "x"
+    ArrayExpression(CArrayExpression),// { 1, 2, 3 }
+    CharExpression(CCharExpression),// 'x', L'x'
     BooleanExpression(CBooleanExpression),// true/false (typeof _Bool - C99+)
-    HexExpression(CNumberExpression),// 0xCAFEBABE
-    OctalExpression(CNumberExpression),// 0123
 
     /* Complex Expressions */
-    TernaryExpression,                  // x == 21 ? {} : {}
-    CastExpression,                     // (int) "123"
-    StructExpression,                   // { fieldName: fieldValue }
+    TernaryExpression(CTernaryExpression),// x == 21 ? a : b
+    CastExpression(CCastExpression),    // (int)(3.14)
+    StructExpression(CStructExpression),// (struct Point){ .x = 1, .y = 2 }
 
     /* Arithmetic Expressions */
-    BinaryExpression,                   // 1 + 1
-    LogicalExpression,                  // 1 == 1
+    BinaryExpression(CBinaryExpression),// 1 + 1, 1 == 1, a && b
+
+    UnaryExpression(CUnaryExpression),  // -a, !a, *a, &b, i++, sizeof(int)
 
     /* Access Expressions */
-    MemberExpression,                   // a->b, a.b
-    IndexExpression,                    // a[b]
-    PointerExpression,                  // *a, &b
+    MemberExpression(CMemberExpression),// a->b, a.b
+    IndexExpression(CIndexExpression),  // a[b]
 
     /* Function Call Expression */
     FunctionCallExpression(CFunctionCallStatement),// printf("Hello, world!\n");
 }
 
-fn get_char_ptr_type() -> &'static CType {
-    unsafe {
-        INIT_CHAR_PTR.call_once(|| {
-            let ptr = CType::Pointer(Box::new(CType::Char));
-            CHAR_PTR_TYPE.write(ptr);
-        });
-        CHAR_PTR_TYPE.assume_init_ref()
+impl CExpression {
+    pub fn get_type(&self) -> Option<CType> {
+        match &self {
+            CExpression::NumberExpression(number) => number.get_type(),
+            CExpression::StringExpression(string) => Some(CType::Pointer(Box::new(string.prefix.element_type()))),
+            CExpression::IdentifierExpression(identifier) => identifier.ctype.clone(),
+            CExpression::DecimalExpression(decimal) => Some(decimal.ctype.clone()),
+            CExpression::ArrayExpression(array) => Some(CType::Array(Box::new(array.ctype.clone()), CArraySize::Constant(array.length))),
+            CExpression::CharExpression(char) => Some(char.prefix.element_type()),
+            CExpression::BooleanExpression(_) => Some(CType::_Bool),
+            CExpression::TernaryExpression(ternary) => ternary.get_type(),
+            CExpression::CastExpression(cast) => Some(cast.ctype.clone()),
+            CExpression::StructExpression(structure) => Some(structure.ctype.clone()),
+            CExpression::BinaryExpression(binary) => binary.get_type(),
+            CExpression::UnaryExpression(unary) => unary.get_type(),
+            CExpression::MemberExpression(_) => None, // Needs the struct/union definition (Codegen::expression_type)
+            CExpression::IndexExpression(index) => index.get_type(),
+            CExpression::FunctionCallExpression(call) => call.get_type(),
+        }
     }
-}
 
-impl CExpression {
-    pub fn get_type(&self) -> Option<&CType> {
+    /* Calls `visit` on the expression and every expression nested in it */
+    pub fn walk<'a>(&'a self, visit: &mut dyn FnMut(&'a CExpression)) {
+        visit(self);
+
+        match &self {
+            CExpression::ArrayExpression(array) => array.cvalue.iter().for_each(|item| item.walk(visit)),
+            CExpression::TernaryExpression(ternary) => {
+                ternary.condition.walk(visit);
+                ternary.then_expression.walk(visit);
+                ternary.else_expression.walk(visit);
+            }
+            CExpression::CastExpression(cast) => cast.expression.walk(visit),
+            CExpression::StructExpression(structure) => structure.fields.iter().for_each(|field| field.value.walk(visit)),
+            CExpression::BinaryExpression(binary) => {
+                binary.left.walk(visit);
+                binary.right.walk(visit);
+            }
+            CExpression::UnaryExpression(CUnaryExpression { operand: CUnaryOperand::Expression(operand), .. }) => operand.walk(visit),
+            CExpression::MemberExpression(member) => member.object.walk(visit),
+            CExpression::IndexExpression(index) => {
+                index.base.walk(visit);
+                index.index.walk(visit);
+            }
+            CExpression::FunctionCallExpression(call) => {
+                call.function.walk(visit);
+                call.args.iter().for_each(|arg| arg.walk(visit));
+            }
+            _ => {}
+        }
+    }
+
+    /* Whether the expression designates an object that can be assigned to */
+    pub fn is_lvalue(&self) -> bool {
+        match &self {
+            CExpression::IdentifierExpression(_) => true,
+            CExpression::UnaryExpression(unary) => unary.operator == CUnaryOperator::Dereference,
+            CExpression::MemberExpression(member) => member.is_arrow || member.object.is_lvalue(),
+            CExpression::IndexExpression(_) => true,
+            // A compound literal is an unnamed object ((struct Point){ .x = 1 }):
+            CExpression::StructExpression(_) => true,
+            _ => false,
+        }
+    }
+
+    /* C operator precedence of the expression, higher binds tighter */
+    pub fn precedence(&self) -> u8 {
         match &self {
-            CExpression::NumberExpression(_) => Some(&CType::Int),
-            CExpression::StringExpression(_) => Some(get_char_ptr_type()),
-            CExpression::IdentifierExpression(_) => None,
-            CExpression::DecimalExpression(decimal) => Some(&decimal.ctype),
-            CExpression::ArrayExpression(array) => Some(&array.ctype),
-            CExpression::CharExpression(_) => Some(&CType::Char),
-            CExpression::BooleanExpression(_) => Some(&CType::_Bool),
-            CExpression::HexExpression(_) => Some(&CType::Int),
-            CExpression::OctalExpression(_) => Some(&CType::Int),
-            CExpression::TernaryExpression => None,
-            CExpression::CastExpression => None, // TODO
-            CExpression::StructExpression => None, // TODO
-            CExpression::BinaryExpression => None, // TODO
-            CExpression::LogicalExpression => None, // TODO
-            CExpression::MemberExpression => None, // TODO
-            CExpression::IndexExpression => None, // TODO
-            CExpression::PointerExpression => None, // TODO
-            CExpression::FunctionCallExpression(_) => None, // TODO
+            CExpression::BinaryExpression(binary) => binary.operator.precedence(),
+            CExpression::UnaryExpression(unary) => unary.operator.precedence(),
+            CExpression::CastExpression(_) => 14,
+            CExpression::DecimalExpression(decimal) if decimal.cvalue.is_sign_negative() => 14,
+            CExpression::TernaryExpression(_) => 3,
+            CExpression::MemberExpression(_) | CExpression::IndexExpression(_) => 15,
+            CExpression::FunctionCallExpression(_) => 15,
+            _ => 16,
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CBinaryOperator {
+    /* Arithmetic Operators */
+    Add,                                // +
+    Subtract,                           // -
+    Multiply,                           // *
+    Divide,                             // /
+    Modulo,                             // %
+
+    /* Bitwise Operators */
+    BitwiseAnd,                         // &
+    BitwiseOr,                          // |
+    BitwiseXor,                         // ^
+
+    /* Shift Operators */
+    ShiftLeft,                          // <<
+    ShiftRight,                         // >>
+
+    /* Comparison Operators */
+    Equal,                              // ==
+    NotEqual,                           // !=
+    LessThan,                           // <
+    LessThanOrEqual,                    // <=
+    GreaterThan,                        // >
+    GreaterThanOrEqual,                 // >=
+
+    /* Logical Operators */
+    LogicalAnd,                         // &&
+    LogicalOr,                          // ||
+}
+
+impl CBinaryOperator {
+    /* C operator precedence, higher binds tighter (all binary operators are left-associative) */
+    pub fn precedence(&self) -> u8 {
+        match self {
+            CBinaryOperator::Multiply | CBinaryOperator::Divide | CBinaryOperator::Modulo => 13,
+            CBinaryOperator::Add | CBinaryOperator::Subtract => 12,
+            CBinaryOperator::ShiftLeft | CBinaryOperator::ShiftRight => 11,
+            CBinaryOperator::LessThan | CBinaryOperator::LessThanOrEqual
+            | CBinaryOperator::GreaterThan | CBinaryOperator::GreaterThanOrEqual => 10,
+            CBinaryOperator::Equal | CBinaryOperator::NotEqual => 9,
+            CBinaryOperator::BitwiseAnd => 8,
+            CBinaryOperator::BitwiseXor => 7,
+            CBinaryOperator::BitwiseOr => 6,
+            CBinaryOperator::LogicalAnd => 5,
+            CBinaryOperator::LogicalOr => 4,
+        }
+    }
+
+    pub fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            CBinaryOperator::Equal | CBinaryOperator::NotEqual
+            | CBinaryOperator::LessThan | CBinaryOperator::LessThanOrEqual
+            | CBinaryOperator::GreaterThan | CBinaryOperator::GreaterThanOrEqual
+        )
+    }
+
+    pub fn is_logical(&self) -> bool {
+        matches!(self, CBinaryOperator::LogicalAnd | CBinaryOperator::LogicalOr)
+    }
+
+    pub fn is_bitwise(&self) -> bool {
+        matches!(self, CBinaryOperator::BitwiseAnd | CBinaryOperator::BitwiseOr | CBinaryOperator::BitwiseXor)
+    }
+
+    pub fn is_shift(&self) -> bool {
+        matches!(self, CBinaryOperator::ShiftLeft | CBinaryOperator::ShiftRight)
+    }
+
+    /* Whether an operand using `inner` reads ambiguously without parentheses (the cases GCC's -Wparentheses flags) */
+    pub fn needs_clarifying_parens(&self, inner: &CBinaryOperator) -> bool {
+        if self == inner {
+            return false;
+        }
+
+        match self {
+            CBinaryOperator::LogicalOr => *inner == CBinaryOperator::LogicalAnd,
+            CBinaryOperator::ShiftLeft | CBinaryOperator::ShiftRight => {
+                matches!(inner, CBinaryOperator::Add | CBinaryOperator::Subtract)
+            }
+            CBinaryOperator::BitwiseAnd | CBinaryOperator::BitwiseOr | CBinaryOperator::BitwiseXor => {
+                inner.is_bitwise() || inner.is_comparison()
+                    || matches!(inner, CBinaryOperator::Add | CBinaryOperator::Subtract)
+            }
+            _ if self.is_comparison() => inner.is_comparison(),
+            _ => false,
+        }
+    }
+}
+
+impl Display for CBinaryOperator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CBinaryOperator::Add => write!(f, "+"),
+            CBinaryOperator::Subtract => write!(f, "-"),
+            CBinaryOperator::Multiply => write!(f, "*"),
+            CBinaryOperator::Divide => write!(f, "/"),
+            CBinaryOperator::Modulo => write!(f, "%"),
+            CBinaryOperator::BitwiseAnd => write!(f, "&"),
+            CBinaryOperator::BitwiseOr => write!(f, "|"),
+            CBinaryOperator::BitwiseXor => write!(f, "^"),
+            CBinaryOperator::ShiftLeft => write!(f, "<<"),
+            CBinaryOperator::ShiftRight => write!(f, ">>"),
+            CBinaryOperator::Equal => write!(f, "=="),
+            CBinaryOperator::NotEqual => write!(f, "!="),
+            CBinaryOperator::LessThan => write!(f, "<"),
+            CBinaryOperator::LessThanOrEqual => write!(f, "<="),
+            CBinaryOperator::GreaterThan => write!(f, ">"),
+            CBinaryOperator::GreaterThanOrEqual => write!(f, ">="),
+            CBinaryOperator::LogicalAnd => write!(f, "&&"),
+            CBinaryOperator::LogicalOr => write!(f, "||"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CBinaryExpression {
+    pub operator: CBinaryOperator,
+    pub left: Box<CExpression>,
+    pub right: Box<CExpression>,
+}
+
+impl CBinaryExpression {
+    pub fn new(left: CExpression, operator: CBinaryOperator, right: CExpression) -> Self {
+        Self { operator, left: Box::new(left), right: Box::new(right) }
+    }
+
+    pub fn get_type(&self) -> Option<CType> {
+        if self.operator.is_comparison() || self.operator.is_logical() {
+            return Some(CType::Int);
+        }
+
+        // A shift has the type of its promoted left operand (short << 1 is an int):
+        if self.operator.is_shift() {
+            return self.left.get_type().map(|left| left.promoted());
+        }
+
+        match (self.left.get_type(), self.right.get_type()) {
+            (Some(left), Some(right)) if left.is_arithmetic() && right.is_arithmetic() => CType::usual_arithmetic_conversion(&left, &right),
+            (Some(left), Some(right)) if left.is_pointer() && right.is_pointer() && self.operator == CBinaryOperator::Subtract => Some(CType::PtrdiffT),
+            (Some(left), Some(right)) if left.is_pointer() && right.is_integer()
+                && matches!(self.operator, CBinaryOperator::Add | CBinaryOperator::Subtract) => Some(left),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CUnaryOperator {
+    /* Pointer Operators */
+    Dereference,                        // *a
+    AddressOf,                          // &a
+
+    /* Arithmetic Operators */
+    Negate,                             // -a
+    LogicalNot,                         // !a
+    BitwiseNot,                         // ~a
+
+    /* Increment/Decrement Operators */
+    PreIncrement,                       // ++a
+    PreDecrement,                       // --a
+    PostIncrement,                      // a++
+    PostDecrement,                      // a--
+
+    /* Size Operators */
+    Sizeof,                             // sizeof(a)
+    Alignof,                            // _Alignof(a)
+}
+
+impl CUnaryOperator {
+    pub fn is_postfix(&self) -> bool {
+        matches!(self, CUnaryOperator::PostIncrement | CUnaryOperator::PostDecrement)
+    }
+
+    /* C operator precedence, higher binds tighter (see `CBinaryOperator::precedence`) */
+    pub fn precedence(&self) -> u8 {
+        if self.is_postfix() { 15 } else { 14 }
+    }
+}
+
+impl Display for CUnaryOperator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CUnaryOperator::Dereference => write!(f, "*"),
+            CUnaryOperator::AddressOf => write!(f, "&"),
+            CUnaryOperator::Negate => write!(f, "-"),
+            CUnaryOperator::LogicalNot => write!(f, "!"),
+            CUnaryOperator::BitwiseNot => write!(f, "~"),
+            CUnaryOperator::PreIncrement | CUnaryOperator::PostIncrement => write!(f, "++"),
+            CUnaryOperator::PreDecrement | CUnaryOperator::PostDecrement => write!(f, "--"),
+            CUnaryOperator::Sizeof => write!(f, "sizeof"),
+            CUnaryOperator::Alignof => write!(f, "_Alignof"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CUnaryOperand {
+    Expression(Box<CExpression>),       // sizeof(x), -x
+    Type(CType),                        // sizeof(int), _Alignof(double)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CUnaryExpression {
+    pub operator: CUnaryOperator,
+    pub operand: CUnaryOperand,
+}
+
+impl CUnaryExpression {
+    pub fn new(operator: CUnaryOperator, operand: CExpression) -> Self {
+        Self { operator, operand: CUnaryOperand::Expression(Box::new(operand)) }
+    }
+
+    /* Creates a `sizeof`/`_Alignof` expression over a type, eg: sizeof(int) */
+    pub fn of_type(operator: CUnaryOperator, ctype: CType) -> Self {
+        Self { operator, operand: CUnaryOperand::Type(ctype) }
+    }
+
+    pub fn get_type(&self) -> Option<CType> {
+        if let CUnaryOperator::Sizeof | CUnaryOperator::Alignof = self.operator {
+            return Some(CType::SizeT);
+        }
+
+        if self.operator == CUnaryOperator::LogicalNot {
+            return Some(CType::Int);
+        }
+
+        let operand_type = match &self.operand {
+            CUnaryOperand::Expression(operand) => operand.get_type()?,
+            CUnaryOperand::Type(_) => return None,
+        };
+
+        match self.operator {
+            CUnaryOperator::Dereference => operand_type.pointee().cloned(),
+            CUnaryOperator::AddressOf => Some(CType::Pointer(Box::new(operand_type))),
+            CUnaryOperator::Negate | CUnaryOperator::BitwiseNot => Some(operand_type.promoted()),
+            _ => Some(operand_type),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CTernaryExpression {
+    pub condition: Box<CExpression>,
+    pub then_expression: Box<CExpression>,
+    pub else_expression: Box<CExpression>,
+}
+
+impl CTernaryExpression {
+    pub fn new(condition: CExpression, then_expression: CExpression, else_expression: CExpression) -> Self {
+        Self {
+            condition: Box::new(condition),
+            then_expression: Box::new(then_expression),
+            else_expression: Box::new(else_expression),
+        }
+    }
+
+    pub fn get_type(&self) -> Option<CType> {
+        let then_type = self.then_expression.get_type()?;
+        let else_type = self.else_expression.get_type()?;
+
+        // Arithmetic arms are converted even when their types match (c ? c : c is an int for a char c):
+        if then_type.is_arithmetic() && else_type.is_arithmetic() {
+            CType::usual_arithmetic_conversion(&then_type, &else_type)
+        } else if then_type == else_type {
+            Some(then_type)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CStructExpression {
+    pub ctype: CType,                   // CustomStructType or CustomUnionType
+    pub fields: Vec<CStructFieldValue>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CStructFieldValue {
+    pub name: String,
+    pub value: CExpression,
+}
+
+impl CStructExpression {
+    pub fn new(ctype: CType, fields: Vec<(&str, CExpression)>) -> Self {
+        Self {
+            ctype,
+            fields: fields.into_iter().map(|(name, value)| CStructFieldValue { name: name.to_string(), value }).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CCastExpression {
+    pub ctype: CType,                   // Target type
+    pub expression: Box<CExpression>,
+}
+
+impl CCastExpression {
+    pub fn new(ctype: CType, expression: CExpression) -> Self {
+        Self { ctype, expression: Box::new(expression) }
+    }
+
+    /* Describes why the cast is lossy or suspicious, if it is (None when the source type is unknown) */
+    pub fn check(&self) -> Option<String> {
+        let from = self.expression.get_type()?;
+        let to = &self.ctype;
+
+        match (from.unqualified(), to.unqualified()) {
+            (CType::Pointer(from_pointee), CType::Pointer(to_pointee)) => {
+                if from_pointee.is_const() && !to_pointee.is_const() {
+                    Some(format!("cast from `{}` to `{}` discards the const qualifier", from, to))
+                } else if from_pointee.unqualified() != to_pointee.unqualified()
+                    && !matches!(from_pointee.unqualified(), CType::Void | CType::Char)
+                    && !matches!(to_pointee.unqualified(), CType::Void | CType::Char) {
+                    Some(format!("cast between incompatible pointer types `{}` and `{}`", from, to))
+                } else {
+                    None
+                }
+            }
+
+            // Casting to void discards the value, (void)(p), and intptr_t/uintptr_t exist to hold pointers:
+            (_, CType::Void) => None,
+            (CType::Pointer(_), CType::IntPtr | CType::UIntPtr) | (CType::IntPtr | CType::UIntPtr, CType::Pointer(_)) => None,
+
+            (CType::Pointer(_), _) if to.is_integer() => {
+                Some(format!("cast from pointer `{}` to integer `{}`", from, to))
+            }
+
+            (CType::Pointer(_), _) => Some(format!("cast from pointer `{}` to non-pointer `{}`", from, to)),
+
+            (_, CType::Pointer(_)) if from.is_integer() => {
+                Some(format!("cast from integer `{}` to pointer `{}`", from, to))
+            }
+
+            _ if from.is_floating() && to.is_integer() => {
+                Some(format!("cast from `{}` to `{}` truncates the fractional part", from, to))
+            }
+
+            _ => match (from.floating_rank(), to.floating_rank(), from.integer_width(), to.integer_width()) {
+                (Some(from_rank), Some(to_rank), _, _) if to_rank < from_rank => {
+                    Some(format!("cast from `{}` to `{}` may lose precision", from, to))
+                }
+
+                // long long and int64_t differ in rank but not in width:
+                (_, _, Some(from_width), Some(to_width)) if to_width < from_width => {
+                    Some(format!("cast from `{}` to `{}` may truncate the value", from, to))
+                }
+
+                _ => None,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CMemberExpression {
+    pub object: Box<CExpression>,
+    pub member: String,
+    pub is_arrow: bool,                 // a->b instead of a.b
+}
+
+impl CMemberExpression {
+    /* Creates a member access through a value (a.b) */
+    pub fn dot(object: CExpression, member: &str) -> Self {
+        Self { object: Box::new(object), member: member.to_string(), is_arrow: false }
+    }
+
+    /* Creates a member access through a pointer (a->b) */
+    pub fn arrow(object: CExpression, member: &str) -> Self {
+        Self { object: Box::new(object), member: member.to_string(), is_arrow: true }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CIndexExpression {
+    pub base: Box<CExpression>,
+    pub index: Box<CExpression>,
+}
+
+impl CIndexExpression {
+    pub fn new(base: CExpression, index: CExpression) -> Self {
+        Self { base: Box::new(base), index: Box::new(index) }
+    }
+
+    pub fn get_type(&self) -> Option<CType> {
+        self.base.get_type()?.pointee().cloned()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct CNumberExpression {
-    pub cvalue: i32,
+    pub cvalue: u64,
+    pub radix: CRadix,
+    pub suffix: CIntegerSuffix,
 }
 
 impl CNumberExpression {
-    pub fn new(number: i32) -> Self {
-        Self { cvalue: number }
+    pub fn new(number: u64) -> Self {
+        Self { cvalue: number, radix: CRadix::Decimal, suffix: CIntegerSuffix::None }
+    }
+
+    pub fn hex(number: u64) -> Self {
+        Self { cvalue: number, radix: CRadix::Hex, suffix: CIntegerSuffix::None }
+    }
+
+    pub fn octal(number: u64) -> Self {
+        Self { cvalue: number, radix: CRadix::Octal, suffix: CIntegerSuffix::None }
+    }
+
+    /* Binary constants are standard since C23 (a GNU extension before), so they are rejected for older standards */
+    pub fn binary(number: u64) -> Self {
+        Self { cvalue: number, radix: CRadix::Binary, suffix: CIntegerSuffix::None }
+    }
+
+    pub fn with_suffix(mut self, suffix: CIntegerSuffix) -> Self {
+        self.suffix = suffix;
+        self
+    }
+
+    /* The type of an integer constant is the first of its candidate types that can represent the value (C99 6.4.4.1) */
+    pub fn get_type(&self) -> Option<CType> {
+        let signed = |ctype| (ctype, false);
+        let unsigned = |ctype| (ctype, true);
+        let long_long = CType::Long(Box::new(CType::Long(Box::new(CType::Int))));
+        let unsigned_long = CType::Unsigned(Box::new(CType::Long(Box::new(CType::Int))));
+        let unsigned_long_long = CType::Unsigned(Box::new(long_long.clone()));
+        let long = CType::Long(Box::new(CType::Int));
+        let unsigned_int = CType::Unsigned(Box::new(CType::Int));
+        let decimal = self.radix == CRadix::Decimal;
+
+        let mut candidates = match self.suffix {
+            CIntegerSuffix::None => vec![signed(CType::Int), unsigned(unsigned_int), signed(long), unsigned(unsigned_long), signed(long_long), unsigned(unsigned_long_long)],
+            CIntegerSuffix::U => vec![unsigned(unsigned_int), unsigned(unsigned_long), unsigned(unsigned_long_long)],
+            CIntegerSuffix::L => vec![signed(long), unsigned(unsigned_long), signed(long_long), unsigned(unsigned_long_long)],
+            CIntegerSuffix::UL => vec![unsigned(unsigned_long), unsigned(unsigned_long_long)],
+            CIntegerSuffix::LL => vec![signed(long_long), unsigned(unsigned_long_long)],
+            CIntegerSuffix::ULL => vec![unsigned(unsigned_long_long)],
+        };
+
+        /* Unsuffixed decimal constants never become unsigned */
+        if decimal && !matches!(self.suffix, CIntegerSuffix::U | CIntegerSuffix::UL | CIntegerSuffix::ULL) {
+            candidates.retain(|(_, is_unsigned)| !is_unsigned);
+        }
+
+        candidates.into_iter()
+            .find(|(ctype, is_unsigned)| {
+                let max = match (ctype.integer_rank(), is_unsigned) {
+                    (Some(rank), false) if rank <= 4 => i32::MAX as u64,
+                    (Some(rank), true) if rank <= 4 => u32::MAX as u64,
+                    (_, false) => i64::MAX as u64,
+                    (_, true) => u64::MAX,
+                };
+                self.cvalue <= max
+            })
+            .map(|(ctype, _)| ctype)
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CRadix {
+    Decimal,                            // 21
+    Hex,                                // 0xCAFEBABE
+    Octal,                              // 0123
+    Binary,                             // 0b101
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum CIntegerSuffix {
+    None,                               // 21
+    U,                                  // 21U
+    L,                                  // 21L
+    UL,                                 // 21UL
+    LL,                                 // 21LL
+    ULL,                                // 21ULL
+}
+
+impl std::fmt::Display for CIntegerSuffix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CIntegerSuffix::None => write!(f, ""),
+            CIntegerSuffix::U => write!(f, "U"),
+            CIntegerSuffix::L => write!(f, "L"),
+            CIntegerSuffix::UL => write!(f, "UL"),
+            CIntegerSuffix::LL => write!(f, "LL"),
+            CIntegerSuffix::ULL => write!(f, "ULL"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct CStringExpression {
-    pub cvalue: String,
-    pub length: usize,
+    pub cvalue: String,                 // Unescaped text of the literal
+    pub length: usize,                  // Number of elements, without the terminating null character
+    pub prefix: CStringPrefix,
 }
 
 impl CStringExpression {
     pub fn new(string: &str) -> Self {
-        Self { cvalue: string.to_string(), length: string.len() }
+        Self { cvalue: string.to_string(), length: string.len(), prefix: CStringPrefix::None }
+    }
+
+    pub fn with_prefix(mut self, prefix: CStringPrefix) -> Self {
+        self.prefix = prefix;
+        self.length = match prefix {
+            CStringPrefix::None | CStringPrefix::Utf8 => self.cvalue.len(),
+            CStringPrefix::Utf16 => self.cvalue.encode_utf16().count(),
+            CStringPrefix::Wide | CStringPrefix::Utf32 => self.cvalue.chars().count(),
+        };
+        self
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CStringPrefix {
+    None,                               // "text", 'c'
+    Wide,                               // L"text" (wchar_t)
+    Utf8,                               // u8"text"
+    Utf16,                              // u"text" (char16_t - C11+)
+    Utf32,                              // U"text" (char32_t - C11+)
+}
+
+impl CStringPrefix {
+    /* The first standard with string literals of this prefix */
+    pub fn standard(&self) -> CStandard {
+        match self {
+            CStringPrefix::None | CStringPrefix::Wide => CStandard::C89,
+            CStringPrefix::Utf8 | CStringPrefix::Utf16 | CStringPrefix::Utf32 => CStandard::C11,
+        }
+    }
+
+    /* The type of one element of a literal with this prefix */
+    pub fn element_type(&self) -> CType {
+        match self {
+            CStringPrefix::None | CStringPrefix::Utf8 => CType::Char,
+            CStringPrefix::Wide => CType::CustomTypedefType("wchar_t".to_string()),
+            CStringPrefix::Utf16 => CType::CustomTypedefType("char16_t".to_string()),
+            CStringPrefix::Utf32 => CType::CustomTypedefType("char32_t".to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for CStringPrefix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CStringPrefix::None => write!(f, ""),
+            CStringPrefix::Wide => write!(f, "L"),
+            CStringPrefix::Utf8 => write!(f, "u8"),
+            CStringPrefix::Utf16 => write!(f, "u"),
+            CStringPrefix::Utf32 => write!(f, "U"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct CIdentifierExpression {
     pub cvalue: String,
+    pub ctype: Option<CType>,           // Declared type of the identifier, if known
 }
 
 impl CIdentifierExpression {
     pub fn new(identifier: &str) -> Self {
-        Self { cvalue: identifier.to_string() }
+        Self { cvalue: identifier.to_string(), ctype: None }
+    }
+
+    pub fn typed(identifier: &str, ctype: CType) -> Self {
+        Self { cvalue: identifier.to_string(), ctype: Some(ctype) }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct CDecimalExpression {
-    pub cvalue: i32,
-    pub ctype: CType,                   // Float or Double
+    pub cvalue: f64,
+    pub ctype: CType,                   // Float, Double or long double
 }
 
 impl CDecimalExpression {
-    pub fn new(decimal: i32, ctype: CType) -> Self {
+    pub fn new(decimal: f64, ctype: CType) -> Self {
         Self { cvalue: decimal, ctype }
     }
+
+    pub fn float(decimal: f32) -> Self {
+        Self { cvalue: decimal as f64, ctype: CType::Float }
+    }
+
+    pub fn double(decimal: f64) -> Self {
+        Self { cvalue: decimal, ctype: CType::Double }
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct CArrayExpression {
     pub cvalue: Vec<CExpression>,
-    pub ctype: CType,
+    pub ctype: CType,                   // Element type
     pub length: usize,
     pub capacity: usize,
 }
@@ -239,71 +984,539 @@ impl CArrayExpression {
         }
     }
 
-    pub fn push(&mut self, item: CExpression) {
+    pub fn push(&mut self, item: CExpression) -> JasmineResult<()> {
         match item.get_type() {
-            Some(ctype) => {
-                if (*ctype == self.ctype) {
-                    self.cvalue.push(item);
-                    self.length += 1;
-                    self.capacity += 1;
-                } else {
-                    // TODO: Use thiserror
-                    panic!("Type error: expected type {:#?}, but found {}", self.ctype, *ctype);
-                }
+            Some(ctype) if ctype != self.ctype => {
+                Err(JasmineError::type_mismatch(format!("Expected an element of type {}, but found {}", self.ctype, ctype))
+                    .in_node(format!("element {}", self.length + 1)))
             }
 
-            None => {}
+            _ => {
+                self.cvalue.push(item);
+                self.length += 1;
+                self.capacity += 1;
+                Ok(())
+            }
         }
     }
 
     // TODO: Add all other array methods.
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct CCharExpression {
     pub cvalue: char,
+    pub prefix: CStringPrefix,
 }
 
 impl CCharExpression {
     pub fn new(char: char) -> Self {
-        Self { cvalue: char }
+        Self { cvalue: char, prefix: CStringPrefix::None }
+    }
+
+    pub fn with_prefix(mut self, prefix: CStringPrefix) -> Self {
+        self.prefix = prefix;
+        self
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct CBooleanExpression {
-    pub cvalue: char,
+    pub cvalue: bool,
 }
 
-#[derive(Debug, Clone)]
+impl CBooleanExpression {
+    pub fn new(boolean: bool) -> Self {
+        Self { cvalue: boolean }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct CFunctionCallStatement {
-    pub function: String,
+    pub function: Box<CExpression>,     // printf, callbacks[i], obj->method, (*fp)
     pub args: Vec<CExpression>,
 }
 
+impl CFunctionCallStatement {
+    /* Creates a call to a function by name */
+    pub fn new(function: &str, args: Vec<CExpression>) -> Self {
+        Self { function: Box::new(CExpression::IdentifierExpression(CIdentifierExpression::new(function))), args }
+    }
+
+    pub fn get_type(&self) -> Option<CType> {
+        match self.function.get_type()? {
+            CType::FunctionPointer { ret, .. } => Some(*ret),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CIncludeStatement {
     pub library: CLibrary,
 }
 
 #[derive(Debug, Clone)]
-pub struct CBlockStatement {
-    pub block: Vec<CStatement>,
+pub struct CTypedefStatement {
+    pub name: String,
+    pub ctype: CType,                   // Aliased type
 }
 
 #[derive(Debug, Clone)]
-pub struct CFunctionStatement {
+pub struct CDefineStatement {
+    pub name: String,
+    pub params: Option<Vec<String>>,    // None for object-like macros
+    pub variadic: bool,                 // #define LOG(fmt, ...)
+    pub body: String,                   // Multi-line bodies are continued with `\`
+}
+
+impl CDefineStatement {
+    /* Creates an object-like macro (#define PI 3.14) */
+    pub fn object(name: &str, body: &str) -> Self {
+        Self { name: name.to_string(), params: None, variadic: false, body: body.to_string() }
+    }
+
+    /* Creates a function-like macro (#define MAX(a, b) ((a) > (b) ? (a) : (b))) */
+    pub fn function(name: &str, params: Vec<&str>, body: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            params: Some(params.into_iter().map(|param| param.to_string()).collect()),
+            variadic: false,
+            body: body.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum CPreprocessorCondition {
+    If(String),                         // #if __STDC_VERSION__ >= 201112L
+    Ifdef(String),                      // #ifdef DEBUG
+    Ifndef(String),                     // #ifndef MAIN_H
+}
+
+#[derive(Debug, Clone)]
+pub struct CConditionalStatement {
+    pub condition: CPreprocessorCondition,
+    pub block: Vec<CProgramNode>,
+    pub elif_blocks: Vec<(String, Vec<CProgramNode>)>,// #elif defined(_WIN32)
+    pub else_block: Option<Vec<CProgramNode>>,
+}
+
+impl CConditionalStatement {
+    /* The macro guarding the block, if this is an include guard (#ifndef MAIN_H followed by #define MAIN_H) */
+    pub fn include_guard(&self) -> Option<&str> {
+        match (&self.condition, self.block.first()) {
+            (CPreprocessorCondition::Ifndef(name), Some(CProgramNode::CStatement(CStatement::DefineStatement(define))))
+                if *name == define.name && define.params.is_none() => Some(name),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum CPragma {
+    Once,                               // #pragma once
+    PackPush(u32),                      // #pragma pack(push, 1)
+    PackPop,                            // #pragma pack(pop)
+    GccDiagnostic(CDiagnosticPragma),   // #pragma GCC diagnostic ignored "-Wunused"
+    Custom(String),                     // #pragma omp parallel for
+}
+
+#[derive(Debug, Clone)]
+pub enum CDiagnosticPragma {
+    Push,                               // push
+    Pop,                                // pop
+    Ignored(String),                    // ignored "-Wunused"
+    Warning(String),                    // warning "-Wunused"
+    Error(String),                      // error "-Wunused"
+}
+
+impl Display for CPragma {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CPragma::Once => write!(f, "once"),
+            CPragma::PackPush(alignment) => write!(f, "pack(push, {})", alignment),
+            CPragma::PackPop => write!(f, "pack(pop)"),
+            CPragma::GccDiagnostic(CDiagnosticPragma::Push) => write!(f, "GCC diagnostic push"),
+            CPragma::GccDiagnostic(CDiagnosticPragma::Pop) => write!(f, "GCC diagnostic pop"),
+            CPragma::GccDiagnostic(CDiagnosticPragma::Ignored(flag)) => write!(f, "GCC diagnostic ignored \"{}\"", flag),
+            CPragma::GccDiagnostic(CDiagnosticPragma::Warning(flag)) => write!(f, "GCC diagnostic warning \"{}\"", flag),
+            CPragma::GccDiagnostic(CDiagnosticPragma::Error(flag)) => write!(f, "GCC diagnostic error \"{}\"", flag),
+            CPragma::Custom(pragma) => write!(f, "{}", pragma),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CBlockStatement {
+    pub block: Vec<CStatement>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CIfStatement {
+    pub condition: CExpression,
+    pub then_block: CBlockStatement,
+    pub else_if_blocks: Vec<CElseIfStatement>,
+    pub else_block: Option<CBlockStatement>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CElseIfStatement {
+    pub condition: CExpression,
+    pub block: CBlockStatement,
+}
+
+#[derive(Debug, Clone)]
+pub struct CWhileStatement {
+    pub condition: CExpression,
+    pub block: CBlockStatement,
+}
+
+#[derive(Debug, Clone)]
+pub struct CDoWhileStatement {
+    pub block: CBlockStatement,
+    pub condition: CExpression,
+}
+
+#[derive(Debug, Clone)]
+pub struct CForStatement {
+    pub init: Option<CForInit>,         // int i = 0
+    pub condition: Option<CExpression>, // i < 27
+    pub step: Option<CExpression>,      // i++
+    pub block: CBlockStatement,
+}
+
+#[derive(Debug, Clone)]
+pub struct CSwitchStatement {
+    pub condition: CExpression,
+    pub cases: Vec<CSwitchCase>,
+    pub default_block: Option<CBlockStatement>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CSwitchCase {
+    pub value: CExpression,             // Integer, char or enum constant
+    pub block: CBlockStatement,
+    pub fallthrough: bool,              // Omits the `break;` inserted after the case
+}
+
+#[derive(Debug, Clone)]
+pub enum CForInit {
+    VariableStatement(CVariableStatement),// for (int i = 0; ...)
+    Expression(CExpression),            // for (i = 0; ...)
+}
+
+#[derive(Debug, Clone)]
+pub struct CFunctionStatement {
     pub function_type: CType,
     pub function_name: String,
     pub function_args: Vec<CFunctionArg>,
-    pub function_block: CBlockStatement,
+    pub function_block: Option<CBlockStatement>,// None for a prototype (int add(int a, int b);)
+    pub storage_class: Option<CStorageClass>,// static int helper()
+    pub specifiers: Vec<CFunctionSpecifier>,// inline, _Noreturn
+    pub variadic: bool,                 // int log(const char *format, ...)
+}
+
+impl CFunctionStatement {
+    pub fn new(function_type: CType, function_name: &str, function_args: Vec<CFunctionArg>, function_block: CBlockStatement) -> Self {
+        Self {
+            function_type,
+            function_name: function_name.to_string(),
+            function_args,
+            function_block: Some(function_block),
+            storage_class: None,
+            specifiers: Vec::new(),
+            variadic: false,
+        }
+    }
+
+    /* Creates a declaration of a function without a body */
+    pub fn prototype(function_type: CType, function_name: &str, function_args: Vec<CFunctionArg>) -> Self {
+        Self { function_block: None, ..Self::new(function_type, function_name, function_args, CBlockStatement { block: Vec::new() }) }
+    }
+
+    /* The names of the functions called by name in the body, in the order of their first call */
+    pub fn called_functions(&self) -> Vec<&str> {
+        let mut calls = Vec::new();
+
+        if let Some(block) = &self.function_block {
+            block.walk(&mut |statement| {
+                if let CStatement::FunctionCallStatement(call) = statement {
+                    calls.push(call);
+                }
+
+                for expression in statement.expressions() {
+                    expression.walk(&mut |expression| {
+                        if let CExpression::FunctionCallExpression(call) = expression {
+                            calls.push(call);
+                        }
+                    });
+                }
+            });
+        }
+
+        let mut called = Vec::new();
+
+        for call in calls {
+            if let CExpression::IdentifierExpression(function) = call.function.as_ref() {
+                if !called.contains(&function.cvalue.as_str()) {
+                    called.push(function.cvalue.as_str());
+                }
+            }
+        }
+
+        called
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CStorageClass {
+    Static,                             // static int counter
+    Extern,                             // extern int shared
+}
+
+impl std::fmt::Display for CStorageClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CStorageClass::Static => write!(f, "static"),
+            CStorageClass::Extern => write!(f, "extern"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CFunctionSpecifier {
+    Inline,                             // inline (C99+)
+    Noreturn,                           // _Noreturn (C11+)
+}
+
+impl CFunctionSpecifier {
+    /* The first C standard with the specifier */
+    pub fn standard(&self) -> CStandard {
+        match self {
+            CFunctionSpecifier::Inline => CStandard::C99,
+            CFunctionSpecifier::Noreturn => CStandard::C11,
+        }
+    }
+}
+
+impl std::fmt::Display for CFunctionSpecifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CFunctionSpecifier::Inline => write!(f, "inline"),
+            CFunctionSpecifier::Noreturn => write!(f, "_Noreturn"),
+        }
+    }
 }
+
 #[derive(Debug, Clone)]
 pub struct CFunctionArg {
     pub r#type: CType,
     pub name: String,
 }
 
+#[derive(Debug, Clone)]
+pub struct CStructDefinition {
+    pub name: String,
+    pub fields: Vec<CStructField>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CUnionDefinition {
+    pub name: String,
+    pub fields: Vec<CStructField>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CStructField {
+    pub r#type: CType,
+    pub name: String,
+    pub bit_width: Option<u32>,         // int flags : 3;
+}
+
+#[derive(Debug, Clone)]
+pub struct CEnumDefinition {
+    pub name: String,
+    pub enumerators: Vec<CEnumerator>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CEnumerator {
+    pub name: String,
+    pub value: Option<i64>,             // RED = 1
+}
+
+impl CEnumDefinition {
+    /* The value of an enumerator, counting up from the previous explicit value like C does */
+    pub fn get_value(&self, name: &str) -> Option<i64> {
+        let mut value = 0;
+
+        for enumerator in &self.enumerators {
+            value = enumerator.value.unwrap_or(value);
+
+            if enumerator.name == name {
+                return Some(value);
+            }
+
+            value += 1;
+        }
+
+        None
+    }
+}
+
+/* The value of a switch case label, used to find duplicate cases */
+#[derive(Debug, Clone, PartialEq)]
+enum CCaseValue {
+    Integer(i128),                      // case 1:, case 'a':, case RED:
+    Constant(String),                   // A constant that is not defined by this program (eg: from a header)
+}
+
+impl CBlockStatement {
+    /* Calls `visit` on every statement of the block, including the ones nested in inner blocks and preprocessor conditionals */
+    pub fn walk<'a>(&'a self, visit: &mut dyn FnMut(&'a CStatement)) {
+        for statement in &self.block {
+            statement.walk(visit);
+        }
+    }
+}
+
+impl CStatement {
+    /* Calls `visit` on the statement and every statement nested in it */
+    pub fn walk<'a>(&'a self, visit: &mut dyn FnMut(&'a CStatement)) {
+        visit(self);
+
+        match self {
+            CStatement::BlockStatement(block) => block.walk(visit),
+            CStatement::IfStatement(stmt) => {
+                stmt.then_block.walk(visit);
+
+                for else_if in &stmt.else_if_blocks {
+                    else_if.block.walk(visit);
+                }
+
+                if let Some(else_block) = &stmt.else_block {
+                    else_block.walk(visit);
+                }
+            }
+            CStatement::WhileStatement(stmt) => stmt.block.walk(visit),
+            CStatement::DoWhileStatement(stmt) => stmt.block.walk(visit),
+            CStatement::ForStatement(stmt) => stmt.block.walk(visit),
+            CStatement::SwitchStatement(stmt) => {
+                for case in &stmt.cases {
+                    case.block.walk(visit);
+                }
+
+                if let Some(default_block) = &stmt.default_block {
+                    default_block.walk(visit);
+                }
+            }
+            CStatement::ConditionalStatement(stmt) => {
+                let branches = std::iter::once(&stmt.block)
+                    .chain(stmt.elif_blocks.iter().map(|(_, block)| block))
+                    .chain(stmt.else_block.iter());
+
+                for node in branches.flatten() {
+                    if let CProgramNode::CStatement(statement) = node {
+                        statement.walk(visit);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /* The expressions that belong to the statement itself, without the ones in its nested blocks */
+    pub fn expressions(&self) -> Vec<&CExpression> {
+        match self {
+            CStatement::VariableStatement(stmt) => stmt.var_value.iter().chain(stmt.declarators.iter().filter_map(|declarator| declarator.value.as_ref())).collect(),
+            CStatement::AssignmentStatement(stmt) => vec![&stmt.target, &stmt.value],
+            CStatement::ReturnStatement(stmt) => vec![&stmt.value],
+            CStatement::FunctionCallStatement(call) => std::iter::once(call.function.as_ref()).chain(&call.args).collect(),
+            CStatement::IfStatement(stmt) => std::iter::once(&stmt.condition).chain(stmt.else_if_blocks.iter().map(|else_if| &else_if.condition)).collect(),
+            CStatement::WhileStatement(stmt) => vec![&stmt.condition],
+            CStatement::DoWhileStatement(stmt) => vec![&stmt.condition],
+            CStatement::ForStatement(stmt) => {
+                let init = match &stmt.init {
+                    Some(CForInit::VariableStatement(variable)) => variable.var_value.iter().chain(variable.declarators.iter().filter_map(|declarator| declarator.value.as_ref())).collect(),
+                    Some(CForInit::Expression(expression)) => vec![expression],
+                    None => Vec::new(),
+                };
+
+                init.into_iter().chain(&stmt.condition).chain(&stmt.step).collect()
+            }
+            CStatement::SwitchStatement(stmt) => std::iter::once(&stmt.condition).chain(stmt.cases.iter().map(|case| &case.value)).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /* The tag declared by a struct/union/enum definition */
+    pub fn type_definition_name(&self) -> Option<&str> {
+        match self {
+            CStatement::StructDefinition(definition) => Some(&definition.name),
+            CStatement::UnionDefinition(definition) => Some(&definition.name),
+            CStatement::EnumDefinition(definition) => Some(&definition.name),
+            _ => None,
+        }
+    }
+
+    /* The tags a struct/union/enum definition needs defined before it */
+    pub fn type_definition_dependencies(&self) -> Vec<&str> {
+        let fields = match self {
+            CStatement::StructDefinition(definition) => &definition.fields,
+            CStatement::UnionDefinition(definition) => &definition.fields,
+            _ => return Vec::new(),
+        };
+
+        fields.iter().filter_map(|field| field.r#type.complete_type_dependency()).collect()
+    }
+
+    /* A short description of the statement, used in the path of an error */
+    pub fn describe(&self) -> String {
+        match self {
+            CStatement::VariableStatement(stmt) => format!("variable {}", stmt.var_name),
+            CStatement::AssignmentStatement(_) => "assignment".to_string(),
+            CStatement::ReturnStatement(_) => "return statement".to_string(),
+            CStatement::FunctionCallStatement(call) => match call.function.as_ref() {
+                CExpression::IdentifierExpression(function) => format!("call to {}", function.cvalue),
+                _ => "function call".to_string(),
+            },
+            CStatement::BreakStatement => "break statement".to_string(),
+            CStatement::ContinueStatement => "continue statement".to_string(),
+            CStatement::GotoStatement(label) => format!("goto {}", label),
+            CStatement::LabelStatement(label) => format!("label {}", label),
+            CStatement::BlockStatement(_) => "block".to_string(),
+            CStatement::IfStatement(_) => "if statement".to_string(),
+            CStatement::WhileStatement(_) => "while loop".to_string(),
+            CStatement::ForStatement(_) => "for loop".to_string(),
+            CStatement::DoWhileStatement(_) => "do-while loop".to_string(),
+            CStatement::SwitchStatement(_) => "switch statement".to_string(),
+            CStatement::FunctionStatement(stmt) => format!("function {}", stmt.function_name),
+            CStatement::StructDefinition(definition) => format!("struct {}", definition.name),
+            CStatement::UnionDefinition(definition) => format!("union {}", definition.name),
+            CStatement::EnumDefinition(definition) => format!("enum {}", definition.name),
+            CStatement::IncludeStatement(stmt) => format!("#include {}", stmt.library.lib_name),
+            CStatement::DefineStatement(stmt) => format!("#define {}", stmt.name),
+            CStatement::UndefStatement(name) => format!("#undef {}", name),
+            CStatement::ConditionalStatement(_) => "preprocessor conditional".to_string(),
+            CStatement::PragmaStatement(pragma) => format!("#pragma {}", pragma),
+            CStatement::TypedefStatement(stmt) => format!("typedef {}", stmt.name),
+        }
+    }
+
+    /* Whether the statement can only appear inside a function body */
+    pub fn is_block_scoped(&self) -> bool {
+        matches!(
+            self,
+            CStatement::AssignmentStatement(_) | CStatement::ReturnStatement(_) | CStatement::FunctionCallStatement(_)
+            | CStatement::BreakStatement | CStatement::ContinueStatement | CStatement::GotoStatement(_)
+            | CStatement::LabelStatement(_) | CStatement::BlockStatement(_) | CStatement::IfStatement(_)
+            | CStatement::WhileStatement(_) | CStatement::ForStatement(_) | CStatement::DoWhileStatement(_)
+            | CStatement::SwitchStatement(_)
+        )
+    }
+}
+
 impl CFile {
     pub fn new(file_name: String, file_path: String, headers: Vec<CLibrary>) -> Self {
         Self { file_name, file_path, headers }
@@ -339,10 +1552,12 @@ impl CLibrary {
 
 impl Display for CFunctionArg {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{} {}", self.r#type, self.name)
+        write!(f, "{}", self.r#type.declaration(&self.name))
     }
 }
 
+// The C Program is matched like every other node, even where it is the only variant handled:
+#[allow(clippy::single_match)]
 impl Codegen {
     /* Creates a new instance of BestJasmine Codegen */
     pub fn new() -> Self {
@@ -351,6 +1566,13 @@ impl Codegen {
         Self {
             c_file: CFile::new("main.c".to_string(), ".".to_string(), Vec::new()),
             c_program,
+            check_casts: false,
+            printer: CPrinter::new(),
+            standard: CStandard::C99,
+            loop_depth: Cell::new(0),
+            switch_depth: Cell::new(0),
+            used_headers: RefCell::new(Vec::new()),
+            warnings: RefCell::new(Vec::new()),
         }
     }
 
@@ -372,6 +1594,11 @@ impl Codegen {
         self.c_file.headers.push(library);
     }
 
+    /* Appends a file-scope variable to the C Program (static int counter = 0;) */
+    pub fn add_variable_statement(&mut self, variable: CVariableStatement) {
+        self.add_top_level_statement(CStatement::VariableStatement(variable));
+    }
+
     /* Appends a function to the C Program */
     pub fn add_function_statement(&mut self, function: CFunctionStatement) {
         match &mut self.c_program {
@@ -386,24 +1613,203 @@ impl Codegen {
         }
     }
     
-    pub fn generate_code(&self) -> String {
+    /* Appends a macro definition to the C Program (#define) */
+    pub fn add_define_statement(&mut self, define: CDefineStatement) {
+        self.add_top_level_statement(CStatement::DefineStatement(define));
+    }
+
+    /* Appends a macro removal to the C Program (#undef) */
+    pub fn add_undef_statement(&mut self, name: &str) {
+        self.add_top_level_statement(CStatement::UndefStatement(name.to_string()));
+    }
+
+    /* Appends a conditional block to the C Program (#if/#ifdef/#ifndef ... #endif) */
+    pub fn add_conditional_statement(&mut self, conditional: CConditionalStatement) {
+        self.add_top_level_statement(CStatement::ConditionalStatement(conditional));
+    }
+
+    /* Appends a pragma to the C Program (#pragma) */
+    pub fn add_pragma_statement(&mut self, pragma: CPragma) {
+        self.add_top_level_statement(CStatement::PragmaStatement(pragma));
+    }
+
+    /* Appends a typedef to the C Program (typedef int JavaScript_Number;) */
+    pub fn add_typedef_statement(&mut self, typedef: CTypedefStatement) {
+        self.add_top_level_statement(CStatement::TypedefStatement(typedef));
+    }
+
+    /* Appends a struct definition to the C Program */
+    pub fn add_struct_definition(&mut self, definition: CStructDefinition) {
+        self.add_top_level_statement(CStatement::StructDefinition(definition));
+    }
+
+    /* Appends a union definition to the C Program */
+    pub fn add_union_definition(&mut self, definition: CUnionDefinition) {
+        self.add_top_level_statement(CStatement::UnionDefinition(definition));
+    }
+
+    /* Appends an enum definition to the C Program */
+    pub fn add_enum_definition(&mut self, definition: CEnumDefinition) {
+        self.add_top_level_statement(CStatement::EnumDefinition(definition));
+    }
+
+    fn add_top_level_statement(&mut self, statement: CStatement) {
+        match &mut self.c_program {
+            CProgramNode::CProgram(ref mut program) => {
+                program.push(CProgramNode::CStatement(statement));
+            }
+            _ => {}
+        }
+    }
+
+    /* Appends a type definition to `emitted`, preceded by the not yet emitted definitions it uses by value */
+    fn order_type_definition<'a>(
+        &self,
+        definition: &'a CStatement,
+        definitions: &[&'a CStatement],
+        visiting: &mut Vec<&'a str>,
+        emitted: &mut Vec<&'a CStatement>,
+    ) -> JasmineResult<()> {
+        let name = definition.type_definition_name().unwrap_or_default();
+
+        // A definition may already have been pulled ahead of the one that uses it, but a tag is only defined once:
+        if emitted.iter().any(|done| std::ptr::eq(*done, definition)) {
+            return Ok(());
+        }
+
+        if emitted.iter().any(|done| done.type_definition_name() == Some(name)) {
+            return Err(JasmineError::invalid_node(format!("The tag {} is already defined", name)));
+        }
+
+        if visiting.contains(&name) {
+            return Err(JasmineError::invalid_node(format!("The type {} recursively contains itself", name)));
+        }
+
+        visiting.push(name);
+
+        for dependency in definition.type_definition_dependencies() {
+            if let Some(dependency) = definitions.iter().find(|def| def.type_definition_name() == Some(dependency)) {
+                self.order_type_definition(dependency, definitions, visiting, emitted)
+                    .map_err(|error| error.in_node(dependency.describe()))?;
+            }
+        }
+
+        visiting.pop();
+        emitted.push(definition);
+        Ok(())
+    }
+
+    /* The warnings found by the last render, when check_casts is set */
+    pub fn warnings(&self) -> Vec<String> {
+        self.warnings.borrow().clone()
+    }
+
+    /* Where the standard headers missing from the program go: after its last include, or else inside a leading include guard
+       or after a leading #pragma once (top of the file otherwise). Returns the top-level node they follow,
+       with how much of that node's code comes before them when they go inside it */
+    fn missing_headers_position(&self, program: &[CProgramNode]) -> JasmineResult<Option<(usize, Option<usize>)>> {
+        let is_include = |node: &CProgramNode| matches!(node, CProgramNode::CStatement(CStatement::IncludeStatement(_)));
+
+        if let Some(index) = program.iter().rposition(is_include) {
+            return Ok(Some((index, None)));
+        }
+
+        match program.first() {
+            Some(CProgramNode::CStatement(CStatement::PragmaStatement(CPragma::Once))) => Ok(Some((0, None))),
+
+            // Inside the guard, after its own includes or its #define:
+            Some(CProgramNode::CStatement(CStatement::ConditionalStatement(guard))) if guard.include_guard().is_some() => {
+                let last = guard.block.iter().rposition(is_include).unwrap_or(0);
+                let mut offset = self.generate_directive(format!("#ifndef {}", guard.include_guard().unwrap_or_default())).len();
+
+                for node in &guard.block[..=last] {
+                    offset += self.generate_program_node(node)?.len();
+                }
+
+                Ok(Some((0, Some(offset))))
+            }
+
+            _ => Ok(None),
+        }
+    }
+
+    /* Renders the C Program into C code, without touching the disk */
+    pub fn render(&self) -> JasmineResult<String> {
         let mut code = String::new();
+        let mut headers_at = 0;
+        self.used_headers.borrow_mut().clear();
+        self.warnings.borrow_mut().clear();
 
         match &self.c_program {
             CProgramNode::CProgram(program) => {
-                for node in program {
+                let definitions: Vec<&CStatement> = program.iter().filter_map(|node| match node {
+                    CProgramNode::CStatement(statement) if statement.type_definition_name().is_some() => Some(statement),
+                    _ => None,
+                }).collect();
+                let mut emitted = Vec::new();
+                let mut declared_functions = Vec::new();
+                let headers_position = self.missing_headers_position(program)?;
+
+                // Definitions with a body are set apart from the nodes around them by a blank line:
+                let mut previous_has_body = false;
+                let mut push_node = |node_code: String, has_body: bool| {
+                    if has_body || previous_has_body {
+                        code.push('\n');
+                    }
+
+                    code.push_str(node_code.as_str());
+                    previous_has_body = has_body;
+                    code.len()
+                };
+
+                for (index, node) in program.iter().enumerate() {
                     match &node {
-                        CProgramNode::CStatement(statement) => {
-                            let statement = self.generate_statement(&statement);
-                            code.push_str(statement.as_str());
+                        // Functions called before they are declared get a prototype ahead of their first caller:
+                        CProgramNode::CStatement(CStatement::FunctionStatement(function)) => {
+                            for called in function.called_functions() {
+                                if called == function.function_name || declared_functions.contains(&called) {
+                                    continue;
+                                }
+
+                                let later_function = self.find_statement(|statement| match statement {
+                                    CStatement::FunctionStatement(later) if later.function_name == called => Some(later),
+                                    _ => None,
+                                });
+
+                                if let Some(later_function) = later_function {
+                                    let prototype = self.generate_function_prototype(later_function)
+                                        .map_err(|error| error.in_node(format!("prototype of function {}", called)))?;
+                                    push_node(prototype, false);
+                                    declared_functions.push(called);
+                                }
+                            }
+
+                            declared_functions.push(&function.function_name);
+                            let function_code = self.generate_function_statement(function)
+                                .map_err(|error| error.in_node(format!("function {}", function.function_name)))?;
+                            push_node(function_code, function.function_block.is_some());
                         }
 
-                        CProgramNode::CExpression(expression) => {
-                            let expression = self.generate_expression(&expression);
-                            code.push_str(expression.as_str());
+                        // Type definitions stay in place unless a definition used by value has to be pulled ahead:
+                        CProgramNode::CStatement(statement) if statement.type_definition_name().is_some() => {
+                            let already_emitted = emitted.len();
+                            self.order_type_definition(statement, &definitions, &mut Vec::new(), &mut emitted)
+                                .map_err(|error| error.in_node(statement.describe()))?;
+
+                            for definition in &emitted[already_emitted..] {
+                                push_node(self.generate_statement(definition).map_err(|error| error.in_node(definition.describe()))?, true);
+                            }
                         }
 
-                        _ => {}
+                        _ => {
+                            let node_code = self.generate_program_node(node)?;
+                            let node_length = node_code.len();
+                            let end = push_node(node_code, false);
+
+                            if let Some((_, offset)) = headers_position.filter(|(after, _)| *after == index) {
+                                headers_at = end - node_length + offset.unwrap_or(node_length);
+                            }
+                        }
                     }
                 }
             }
@@ -411,25 +1817,96 @@ impl Codegen {
             _ => {}
         }
 
-        /* Save to file */
+        // Headers for the standard types used by the program (size_t, int32_t, bool, NAN, ...) that were not included:
+        let missing_headers = self.used_headers.borrow().iter()
+            .filter(|header| self.find_statement(|statement| match statement {
+                CStatement::IncludeStatement(include) if include.library.lib_name == **header => Some(()),
+                _ => None,
+            }).is_none())
+            .map(|header| format!("\n#include <{}>", header))
+            .collect::<String>();
+        code.insert_str(headers_at, &missing_headers);
+
+        // Every top-level node starts on a new line, so the first one leaves a leading newline behind:
+        let mut code = code.trim_start_matches('\n').to_string();
+
+        if !code.is_empty() {
+            code.push('\n');
+        }
+
+        Ok(code)
+    }
 
-        match File::create(
-            self.c_file.get_file_path()
-        ) {
-            // TODO: Use thiserror for error handling
-            Ok(mut file) => {
-                match file.write_all(format!("{}", code).as_bytes()) {
-                    Ok(_) => {},
-                    Err(e) => eprintln!("Failed to write to file: {}", e),
-                }
-            }
-            Err(e) => eprintln!("Failed to create or overwrite file: {}", e),
+    /* Renders the C Program into `writer` (a file, stdout, a buffer, ...) */
+    pub fn write_to(&self, mut writer: impl Write) -> JasmineResult<()> {
+        let code = self.render()?;
+
+        writer.write_all(code.as_bytes()).map_err(|error| JasmineError::io("output", error))
+    }
+
+    /* Renders the C Program into the file at `path`, returning whether the file was written.
+       A file that already holds the same code is left alone (so its modification time is kept),
+       and a changed file is replaced through a temporary file, so it is never seen half written */
+    pub fn emit_to_file(&self, path: impl AsRef<Path>) -> JasmineResult<bool> {
+        let path = path.as_ref();
+        let code = self.render()?;
+
+        if fs::read(path).is_ok_and(|existing| existing == code.as_bytes()) {
+            return Ok(false);
         }
 
-        code
+        let Some(file_name) = path.file_name() else {
+            let error = io::Error::new(io::ErrorKind::InvalidInput, "the path does not name a file");
+            return Err(JasmineError::io(&path.display().to_string(), error));
+        };
+
+        // The temporary file sits next to the target, as a rename cannot move a file across file systems,
+        // and is unique to this call, so concurrent emits (from any thread or process) never share one:
+        static TEMP_FILES: AtomicUsize = AtomicUsize::new(0);
+        let temp_path = path.with_file_name(format!(
+            ".{}.{}.{}.tmp",
+            file_name.to_string_lossy(),
+            process::id(),
+            TEMP_FILES.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let written = File::create(&temp_path)
+            .and_then(|mut file| {
+                file.write_all(code.as_bytes())?;
+                file.sync_all()
+            })
+            .and_then(|_| fs::rename(&temp_path, path));
+
+        if let Err(error) = written {
+            let _ = fs::remove_file(&temp_path);
+            return Err(JasmineError::io(&path.display().to_string(), error));
+        }
+
+        Ok(true)
+    }
+
+    /* Generates a top-level node, or a node of a preprocessor conditional at file scope */
+    pub fn generate_program_node(&self, node: &CProgramNode) -> JasmineResult<String> {
+        match node {
+            CProgramNode::CStatement(statement) if statement.is_block_scoped() && self.printer.depth() == 0 => {
+                Err(JasmineError::unsupported_node(format!("The {} can only appear inside a function", statement.describe())))
+            }
+
+            CProgramNode::CStatement(statement) => {
+                self.generate_statement(statement).map_err(|error| error.in_node(statement.describe()))
+            }
+
+            CProgramNode::CExpression(_) if self.printer.depth() == 0 => {
+                Err(JasmineError::unsupported_node("An expression can only appear inside a function".to_string()))
+            }
+
+            CProgramNode::CExpression(expression) => self.generate_expression(expression),
+
+            CProgramNode::CProgram(program) => program.iter().map(|node| self.generate_program_node(node)).collect(),
+        }
     }
 
-    pub fn generate_statement(&self, node: &CStatement) -> String {
+    pub fn generate_statement(&self, node: &CStatement) -> JasmineResult<String> {
         match node {
             CStatement::VariableStatement(stmt) => {
                 self.generate_c_variable_statement(stmt)
@@ -448,23 +1925,90 @@ impl Codegen {
             }
 
             CStatement::IncludeStatement(stmt) => {
-                self.generate_include_statement(stmt)
+                Ok(self.generate_include_statement(stmt))
             }
 
-            CStatement::FunctionStatement(stmt) => {
-                self.generate_function_statement(stmt)
+            CStatement::DefineStatement(stmt) => {
+                Ok(self.generate_define_statement(stmt))
             }
 
-            CStatement::BlockStatement(stmt) => {
-                self.generate_block_statement(stmt)
+            CStatement::UndefStatement(name) => {
+                Ok(self.generate_directive(format!("#undef {}", name)))
             }
 
-            _ => { todo!() }
-        }
-    }
+            CStatement::ConditionalStatement(stmt) => {
+                self.generate_conditional_statement(stmt)
+            }
 
-    pub fn generate_expression(&self, node: &CExpression) -> String {
-        match &node {
+            CStatement::PragmaStatement(pragma) => {
+                Ok(self.generate_directive(format!("#pragma {}", pragma)))
+            }
+
+            CStatement::TypedefStatement(stmt) => {
+                self.generate_typedef_statement(stmt)
+            }
+
+            CStatement::FunctionStatement(stmt) => {
+                self.generate_function_statement(stmt)
+            }
+
+            CStatement::BlockStatement(stmt) => {
+                let indent = self.printer.indent();
+                Ok(format!("{indent}{{\n{}{indent}}}\n", self.printer.nested(1, || self.generate_block_statement(stmt))?))
+            }
+
+            CStatement::IfStatement(stmt) => {
+                self.generate_if_statement(stmt)
+            }
+
+            CStatement::StructDefinition(stmt) => {
+                self.generate_struct_definition(stmt)
+            }
+
+            CStatement::UnionDefinition(stmt) => {
+                self.generate_union_definition(stmt)
+            }
+
+            CStatement::EnumDefinition(stmt) => {
+                self.generate_enum_definition(stmt)
+            }
+
+            CStatement::WhileStatement(stmt) => {
+                self.generate_while_statement(stmt)
+            }
+
+            CStatement::DoWhileStatement(stmt) => {
+                self.generate_do_while_statement(stmt)
+            }
+
+            CStatement::ForStatement(stmt) => {
+                self.generate_for_statement(stmt)
+            }
+
+            CStatement::SwitchStatement(stmt) => {
+                self.generate_switch_statement(stmt)
+            }
+
+            CStatement::BreakStatement => {
+                self.generate_break_statement()
+            }
+
+            CStatement::ContinueStatement => {
+                self.generate_continue_statement()
+            }
+
+            CStatement::GotoStatement(label) => {
+                Ok(format!("{}goto {};\n", self.printer.indent(), label))
+            }
+
+            CStatement::LabelStatement(label) => {
+                Ok(self.generate_label_statement(label, false))
+            }
+        }
+    }
+
+    pub fn generate_expression(&self, node: &CExpression) -> JasmineResult<String> {
+        match &node {
             CExpression::NumberExpression(expr) => {
                 self.generate_c_number_expression(expr)
             }
@@ -473,85 +2017,1250 @@ impl Codegen {
                 self.generate_c_string_expression(expr)
             }
 
-            CExpression::HexExpression(expr) => {
-                self.generate_c_number_expression(expr)
+            CExpression::DecimalExpression(expr) => {
+                self.generate_c_decimal_expression(expr)
             }
 
-            CExpression::OctalExpression(expr) => {
-                self.generate_c_number_expression(expr)
+            CExpression::IdentifierExpression(expr) => {
+                Ok(self.generate_c_identifier_expression(expr))
+            }
+
+            CExpression::CharExpression(expr) => {
+                self.generate_c_char_expression(expr)
+            }
+
+            CExpression::BooleanExpression(expr) => {
+                Ok(self.generate_c_boolean_expression(expr))
+            }
+
+            CExpression::ArrayExpression(expr) => {
+                self.generate_c_array_expression(expr)
             }
 
             CExpression::FunctionCallExpression(expr) => {
-                self.generate_c_function_call_statement(expr)
+                self.generate_c_function_call_expression(expr)
+            }
+
+            CExpression::BinaryExpression(expr) => {
+                self.generate_c_binary_expression(expr)
+            }
+
+            CExpression::UnaryExpression(expr) => {
+                self.generate_c_unary_expression(expr)
+            }
+
+            CExpression::TernaryExpression(expr) => {
+                self.generate_c_ternary_expression(expr)
+            }
+
+            CExpression::CastExpression(expr) => {
+                self.generate_c_cast_expression(expr)
+            }
+
+            CExpression::StructExpression(expr) => {
+                self.generate_c_struct_expression(expr)
+            }
+
+            CExpression::MemberExpression(expr) => {
+                self.generate_c_member_expression(expr)
+            }
+
+            CExpression::IndexExpression(expr) => {
+                self.generate_c_index_expression(expr)
+            }
+        }
+    }
+
+    pub fn generate_c_number_expression(&self, expr: &CNumberExpression) -> JasmineResult<String> {
+        // Only an unsigned suffix gives a decimal constant above LLONG_MAX a type:
+        if expr.get_type().is_none() {
+            return Err(JasmineError::invalid_node(format!("The constant {} does not fit in any signed type, add an unsigned suffix (U)", expr.cvalue)));
+        }
+
+        if expr.radix == CRadix::Binary && self.standard < CStandard::C23 {
+            return Err(JasmineError::invalid_node(format!("The binary constant 0b{:b} needs C23 or later", expr.cvalue)));
+        }
+
+        Ok(match expr.radix {
+            CRadix::Decimal => format!("{}{}", expr.cvalue, expr.suffix),
+            CRadix::Hex => format!("0x{:X}{}", expr.cvalue, expr.suffix),
+            CRadix::Octal if expr.cvalue == 0 => format!("0{}", expr.suffix),
+            CRadix::Octal => format!("0{:o}{}", expr.cvalue, expr.suffix),
+            CRadix::Binary => format!("0b{:b}{}", expr.cvalue, expr.suffix),
+        })
+    }
+
+    /* Emits the shortest literal that reads back as the same value, suffixed with the precision of its type */
+    pub fn generate_c_decimal_expression(&self, expr: &CDecimalExpression) -> JasmineResult<String> {
+        let suffix = match expr.ctype.unqualified() {
+            CType::Float => "f",
+            CType::Long(_) => "L",
+            _ => "",
+        };
+
+        /* NAN and INFINITY have type float and convert exactly to the wider floating types */
+        if !expr.cvalue.is_finite() {
+            self.record_header("math.h");
+
+            return Ok(if expr.cvalue.is_nan() {
+                "NAN".to_string()
+            } else if expr.cvalue.is_sign_negative() {
+                "-INFINITY".to_string()
+            } else {
+                "INFINITY".to_string()
+            });
+        }
+
+        if suffix != "f" {
+            return Ok(format!("{:?}{}", expr.cvalue, suffix));
+        }
+
+        // A finite double can still overflow or underflow a float:
+        let value = expr.cvalue as f32;
+
+        if !value.is_finite() || (value == 0.0 && expr.cvalue != 0.0) {
+            return Err(JasmineError::invalid_node(format!("The constant {:?} is out of the range of float", expr.cvalue)));
+        }
+
+        Ok(format!("{:?}{}", value, suffix))
+    }
+
+    pub fn generate_c_string_expression(&self, expr: &CStringExpression) -> JasmineResult<String> {
+        self.check_prefix(expr.prefix, expr.prefix.standard(), "string literals")?;
+
+        Ok(format!("{}\"{}\"", expr.prefix, escape_literal(&expr.cvalue, '"', expr.prefix)))
+    }
+
+    pub fn generate_c_char_expression(&self, expr: &CCharExpression) -> JasmineResult<String> {
+        // u8 character constants came after u8 string literals:
+        let standard = if expr.prefix == CStringPrefix::Utf8 { CStandard::C23 } else { expr.prefix.standard() };
+        self.check_prefix(expr.prefix, standard, "character constants")?;
+
+        // A plain or u8 character constant holds a single byte, and a u'' one a single UTF-16 code unit:
+        let fits = match expr.prefix {
+            CStringPrefix::None | CStringPrefix::Utf8 => expr.cvalue.is_ascii(),
+            CStringPrefix::Utf16 => expr.cvalue.len_utf16() == 1,
+            CStringPrefix::Wide | CStringPrefix::Utf32 => true,
+        };
+
+        if !fits {
+            return Err(JasmineError::invalid_node(format!(
+                "The character {:?} does not fit in a {}character constant, use a wider prefix (L, U)",
+                expr.cvalue,
+                if expr.prefix == CStringPrefix::None { String::new() } else { format!("{}'' ", expr.prefix) }
+            )));
+        }
+
+        Ok(format!("{}'{}'", expr.prefix, escape_literal(&expr.cvalue.to_string(), '\'', expr.prefix)))
+    }
+
+    /* Checks that the target standard has literals with the encoding prefix */
+    fn check_prefix(&self, prefix: CStringPrefix, standard: CStandard, literals: &str) -> JasmineResult<()> {
+        if self.standard < standard {
+            return Err(JasmineError::invalid_node(format!("{} {} need {:?} or later", prefix, literals, standard)));
+        }
+
+        Ok(())
+    }
+
+    /* _Bool has no true and false before C99, and they are keywords since C23 */
+    pub fn generate_c_boolean_expression(&self, expr: &CBooleanExpression) -> String {
+        if self.standard < CStandard::C99 {
+            return if expr.cvalue { "1" } else { "0" }.to_string();
+        }
+
+        if self.standard < CStandard::C23 {
+            self.record_header("stdbool.h");
+        }
+
+        if expr.cvalue { "true" } else { "false" }.to_string()
+    }
+
+    /* Generates the brace initializer of an array ({ 1, 2, 3 }) */
+    pub fn generate_c_array_expression(&self, expr: &CArrayExpression) -> JasmineResult<String> {
+        if expr.cvalue.is_empty() {
+            return Ok("{ 0 }".to_string());
+        }
+
+        let items = expr.cvalue.iter().enumerate()
+            .map(|(index, item)| self.generate_expression(item).map_err(|error| error.in_node(format!("element {}", index + 1))))
+            .collect::<JasmineResult<Vec<_>>>()?;
+
+        Ok(format!("{{ {} }}", items.join(", ")))
+    }
+
+    pub fn generate_c_identifier_expression(&self, expr: &CIdentifierExpression) -> String {
+        expr.cvalue.clone()
+    }
+
+    pub fn generate_c_binary_expression(&self, expr: &CBinaryExpression) -> JasmineResult<String> {
+        Ok(format!(
+            "{} {} {}",
+            self.generate_c_binary_operand(&expr.operator, &expr.left, false)?,
+            expr.operator,
+            self.generate_c_binary_operand(&expr.operator, &expr.right, true)?
+        ))
+    }
+
+    /* Generates an operand of a binary expression, parenthesized only where precedence, associativity or readability require it */
+    fn generate_c_binary_operand(&self, operator: &CBinaryOperator, operand: &CExpression, is_right: bool) -> JasmineResult<String> {
+        let precedence = operator.precedence();
+        let operand_precedence = operand.precedence();
+
+        let needs_parens = operand_precedence < precedence
+            || (is_right && operand_precedence == precedence)
+            || matches!(operand, CExpression::BinaryExpression(inner) if operator.needs_clarifying_parens(&inner.operator));
+
+        if needs_parens {
+            Ok(format!("({})", self.generate_expression(operand)?))
+        } else {
+            self.generate_expression(operand)
+        }
+    }
+
+    pub fn generate_c_unary_expression(&self, expr: &CUnaryExpression) -> JasmineResult<String> {
+        if expr.operator == CUnaryOperator::Alignof && self.standard < CStandard::C11 {
+            return Err(JasmineError::invalid_node("_Alignof needs C11 or later".to_string()));
+        }
+
+        let operand = match (&expr.operand, expr.operator) {
+            (CUnaryOperand::Type(ctype), CUnaryOperator::Sizeof | CUnaryOperator::Alignof) => {
+                self.check_type(ctype)?;
+                return Ok(format!("{}({})", expr.operator, ctype));
+            }
+
+            (CUnaryOperand::Type(ctype), _) => {
+                return Err(JasmineError::invalid_node(format!("The operator {} cannot be applied to the type {}", expr.operator, ctype)));
+            }
+
+            // ISO C only takes the alignment of a type name, _Alignof(x) is a GNU extension:
+            (CUnaryOperand::Expression(_), CUnaryOperator::Alignof) => {
+                return Err(JasmineError::invalid_node("_Alignof takes a type name, not an expression".to_string()));
+            }
+
+            (CUnaryOperand::Expression(operand), _) => operand,
+        };
+
+        if expr.operator == CUnaryOperator::Sizeof {
+            return Ok(format!("{}({})", expr.operator, self.generate_expression(operand)?));
+        }
+
+        let needs_lvalue = matches!(
+            expr.operator,
+            CUnaryOperator::AddressOf | CUnaryOperator::PreIncrement | CUnaryOperator::PreDecrement
+            | CUnaryOperator::PostIncrement | CUnaryOperator::PostDecrement
+        );
+
+        if needs_lvalue && !operand.is_lvalue() {
+            return Err(JasmineError::invalid_node(format!("The operand of {} is not an lvalue", expr.operator)));
+        }
+
+        let mut code = self.generate_expression(operand)?;
+
+        // Keeps `-(-a)`, `-(-3.0)` and `&(&a)` from collapsing into the `--a` and `&&a` tokens:
+        let is_ambiguous = match operand.as_ref() {
+            CExpression::UnaryExpression(inner) => matches!(
+                (expr.operator, inner.operator),
+                (CUnaryOperator::Negate | CUnaryOperator::PreDecrement, CUnaryOperator::Negate | CUnaryOperator::PreDecrement)
+                | (CUnaryOperator::PreIncrement, CUnaryOperator::PreIncrement)
+                | (CUnaryOperator::AddressOf, CUnaryOperator::AddressOf)
+            ),
+            // A negative decimal literal starts with its own minus sign (-3.0):
+            CExpression::DecimalExpression(decimal) => {
+                decimal.cvalue.is_sign_negative() && matches!(expr.operator, CUnaryOperator::Negate | CUnaryOperator::PreDecrement)
+            }
+            _ => false,
+        };
+
+        if operand.precedence() < expr.operator.precedence() || is_ambiguous {
+            code = format!("({})", code);
+        }
+
+        if expr.operator.is_postfix() {
+            Ok(format!("{}{}", code, expr.operator))
+        } else {
+            Ok(format!("{}{}", expr.operator, code))
+        }
+    }
+
+    pub fn generate_c_ternary_expression(&self, expr: &CTernaryExpression) -> JasmineResult<String> {
+        // The conditional operator is right-associative, so only a nested condition needs parentheses:
+        let condition = if expr.condition.precedence() <= 3 {
+            format!("({})", self.generate_expression(&expr.condition)?)
+        } else {
+            self.generate_expression(&expr.condition)?
+        };
+
+        Ok(format!(
+            "{} ? {} : {}",
+            condition,
+            self.generate_expression(&expr.then_expression)?,
+            self.generate_expression(&expr.else_expression)?
+        ))
+    }
+
+    pub fn generate_c_cast_expression(&self, expr: &CCastExpression) -> JasmineResult<String> {
+        self.check_type(&expr.ctype)?;
+
+        // Only scalar types and void can be cast to:
+        if let CType::CustomStructType(_) | CType::CustomUnionType(_) | CType::Array(..) = self.resolve_type(&expr.ctype).unqualified() {
+            return Err(JasmineError::type_mismatch(format!("Cannot cast to the type {}, only to scalar types and void", expr.ctype)));
+        }
+
+        if self.check_casts {
+            if let Some(warning) = expr.check() {
+                self.warnings.borrow_mut().push(warning);
+            }
+        }
+
+        Ok(format!("({})({})", expr.ctype, self.generate_expression(&expr.expression)?))
+    }
+
+    pub fn generate_c_struct_expression(&self, expr: &CStructExpression) -> JasmineResult<String> {
+        Ok(format!("({}){}", expr.ctype, self.generate_c_struct_initializer(expr)?))
+    }
+
+    /* Generates the designated initializer list of a struct expression ({ .x = 1, .y = 2 }) */
+    pub fn generate_c_struct_initializer(&self, expr: &CStructExpression) -> JasmineResult<String> {
+        self.check_struct_expression(expr)?;
+
+        if expr.fields.is_empty() {
+            return Ok("{ 0 }".to_string());
+        }
+
+        let fields = expr.fields.iter()
+            .map(|field| {
+                let value = self.generate_expression(&field.value).map_err(|error| error.in_node(format!("field .{}", field.name)))?;
+                Ok(format!(".{} = {}", field.name, value))
+            })
+            .collect::<JasmineResult<Vec<_>>>()?;
+
+        Ok(format!("{{ {} }}", fields.join(", ")))
+    }
+
+    /* Checks the fields of a struct expression against its struct/union definition, if one was added */
+    fn check_struct_expression(&self, expr: &CStructExpression) -> JasmineResult<()> {
+        let ctype = self.resolve_type(&expr.ctype);
+
+        if !matches!(ctype.unqualified(), CType::CustomStructType(_) | CType::CustomUnionType(_)) {
+            return Err(JasmineError::type_mismatch(format!("The type {} is not a struct or union type", expr.ctype)));
+        }
+
+        let definition_fields = self.find_fields(&ctype);
+
+        for (index, field) in expr.fields.iter().enumerate() {
+            if expr.fields[..index].iter().any(|previous| previous.name == field.name) {
+                return Err(JasmineError::invalid_node(format!("The field {} is initialized more than once in {}", field.name, expr.ctype)));
+            }
+
+            if let Some(definition_fields) = definition_fields {
+                if !definition_fields.iter().any(|definition_field| definition_field.name == field.name) {
+                    return Err(JasmineError::undefined_identifier(&field.name, format!("{} has no field named {}", expr.ctype, field.name)));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /* Checks the specifiers of a generated type and records the standard headers it needs */
+    fn check_type(&self, ctype: &CType) -> JasmineResult<()> {
+        ctype.validate().map_err(JasmineError::invalid_node)?;
+
+        if self.standard < CStandard::C99 && ctype.uses_bool() {
+            return Err(JasmineError::invalid_node(format!("The type {} needs C99 or later", ctype)));
+        }
+
+        let mut headers = Vec::new();
+        ctype.required_headers(&mut headers);
+
+        for header in headers {
+            if header == "uchar.h" && self.standard < CStandard::C11 {
+                return Err(JasmineError::invalid_node(format!("The type {} needs C11 or later (<uchar.h>)", ctype)));
+            }
+
+            // bool is a keyword since C23:
+            if header != "stdbool.h" || self.standard < CStandard::C23 {
+                self.record_header(header);
+            }
+        }
+
+        Ok(())
+    }
+
+    /* Records a standard header that the generated code needs */
+    fn record_header(&self, header: &'static str) {
+        let mut used_headers = self.used_headers.borrow_mut();
+
+        if !used_headers.contains(&header) {
+            used_headers.push(header);
+        }
+    }
+
+    /* Expands every typedef name (and the bool macro) in a type into the type it aliases */
+    pub fn resolve_type(&self, ctype: &CType) -> CType {
+        self.resolve_type_through(ctype, &mut Vec::new())
+    }
+
+    /* `aliases` holds the typedef names being expanded, so a typedef cycle (typedef A B; typedef B A;) stops at the repeated name */
+    fn resolve_type_through(&self, ctype: &CType, aliases: &mut Vec<String>) -> CType {
+        if let CType::CustomTypedefType(name) = ctype {
+            let aliased = self.find_statement(|statement| match statement {
+                CStatement::TypedefStatement(typedef) if &typedef.name == name => Some(&typedef.ctype),
+                _ => None,
+            });
+
+            return match aliased {
+                Some(aliased) if !aliases.contains(name) => {
+                    aliases.push(name.clone());
+                    let resolved = self.resolve_type_through(aliased, aliases);
+                    aliases.pop();
+
+                    resolved
+                }
+                _ => ctype.clone(),
+            };
+        }
+
+        let mut resolve = |inner: &CType| Box::new(self.resolve_type_through(inner, aliases));
+
+        match ctype {
+            // <stdbool.h> defines bool as a macro for _Bool:
+            CType::Bool => CType::_Bool,
+
+            CType::Pointer(inner) => CType::Pointer(resolve(inner)),
+            CType::Array(inner, size) => CType::Array(resolve(inner), size.clone()),
+            CType::Const(inner) => CType::Const(resolve(inner)),
+            CType::Volatile(inner) => CType::Volatile(resolve(inner)),
+            CType::Restrict(inner) => CType::Restrict(resolve(inner)),
+            CType::Short(inner) => CType::Short(resolve(inner)),
+            CType::Long(inner) => CType::Long(resolve(inner)),
+            CType::Unsigned(inner) => CType::Unsigned(resolve(inner)),
+            CType::Signed(inner) => CType::Signed(resolve(inner)),
+            _ => ctype.clone(),
+        }
+    }
+
+    /* Finds the first top-level statement of the C Program matching `find` */
+    fn find_statement<'a, T>(&'a self, find: impl Fn(&'a CStatement) -> Option<T>) -> Option<T> {
+        match &self.c_program {
+            CProgramNode::CProgram(program) => program.iter().find_map(|node| match node {
+                CProgramNode::CStatement(statement) => find(statement),
+                _ => None,
+            }),
+            _ => None,
+        }
+    }
+
+    /* The fields of the struct/union definition of a resolved type (None for other types, or when it was not added) */
+    fn find_fields(&self, ctype: &CType) -> Option<&Vec<CStructField>> {
+        match ctype.unqualified() {
+            CType::CustomStructType(name) => self.find_statement(|statement| match statement {
+                CStatement::StructDefinition(definition) if &definition.name == name => Some(&definition.fields),
+                _ => None,
+            }),
+            CType::CustomUnionType(name) => self.find_statement(|statement| match statement {
+                CStatement::UnionDefinition(definition) if &definition.name == name => Some(&definition.fields),
+                _ => None,
+            }),
+            _ => None,
+        }
+    }
+
+    /* The type of an expression, with member accesses looked up in the struct/union definitions */
+    pub fn expression_type(&self, expr: &CExpression) -> Option<CType> {
+        match expr {
+            CExpression::MemberExpression(member) => self.member_type(member).ok().flatten(),
+            _ => expr.get_type(),
+        }
+    }
+
+    /* The type of the member a member access reads (None when the type of the object or its definition is unknown) */
+    fn member_type(&self, expr: &CMemberExpression) -> JasmineResult<Option<CType>> {
+        let object_type = match self.expression_type(&expr.object) {
+            Some(object_type) => self.resolve_type(&object_type),
+            None => return Ok(None),
+        };
+
+        let record = match (expr.is_arrow, object_type.pointee()) {
+            (true, Some(pointee)) => pointee.clone(),
+            (false, None) => object_type,
+            (true, None) => return Err(JasmineError::type_mismatch(format!("The type {} is not a pointer, use . to access {}", object_type, expr.member))),
+            (false, Some(_)) => return Err(JasmineError::type_mismatch(format!("The type {} is a pointer or an array, use -> to access {}", object_type, expr.member))),
+        };
+
+        if !matches!(record.unqualified(), CType::CustomStructType(_) | CType::CustomUnionType(_)) {
+            return Err(JasmineError::type_mismatch(format!("The type {} is not a struct or union type", record)));
+        }
+
+        match self.find_fields(&record) {
+            Some(fields) => match fields.iter().find(|field| field.name == expr.member) {
+                Some(field) => Ok(Some(field.r#type.clone())),
+                None => Err(JasmineError::undefined_identifier(&expr.member, format!("{} has no field named {}", record, expr.member))),
+            },
+            None => Ok(None),
+        }
+    }
+
+    pub fn generate_c_member_expression(&self, expr: &CMemberExpression) -> JasmineResult<String> {
+        self.member_type(expr)?;
+
+        Ok(format!(
+            "{}{}{}",
+            self.generate_c_postfix_operand(&expr.object)?,
+            if expr.is_arrow { "->" } else { "." },
+            expr.member
+        ))
+    }
+
+    pub fn generate_c_index_expression(&self, expr: &CIndexExpression) -> JasmineResult<String> {
+        Ok(format!("{}[{}]", self.generate_c_postfix_operand(&expr.base)?, self.generate_expression(&expr.index)?))
+    }
+
+    /* Generates the operand of a postfix expression (a.b, a->b, a[b]), parenthesized if it binds looser */
+    fn generate_c_postfix_operand(&self, operand: &CExpression) -> JasmineResult<String> {
+        if operand.precedence() < 15 {
+            Ok(format!("({})", self.generate_expression(operand)?))
+        } else {
+            self.generate_expression(operand)
+        }
+    }
+
+    pub fn generate_c_variable_statement(&self, node: &CVariableStatement) -> JasmineResult<String> {
+        // Variables at file scope are top-level nodes like functions and type definitions:
+        if self.printer.depth() == 0 {
+            return Ok(format!("\n{};", self.generate_c_variable_declaration(node)?));
+        }
+
+        Ok(format!("{}{};\n", self.printer.indent(), self.generate_c_variable_declaration(node)?))
+    }
+
+    /* Generates a variable declaration without indentation or a trailing semicolon (static int x = 21, y) */
+    pub fn generate_c_variable_declaration(&self, node: &CVariableStatement) -> JasmineResult<String> {
+        self.check_type(&node.var_type)?;
+
+        let block_scope = self.printer.depth() > 0;
+
+        // Unlike a function specifier, the storage duration changes what the program means, so it cannot be dropped:
+        if node.thread_local && self.standard < CStandard::C11 {
+            return Err(JasmineError::invalid_node(format!("The _Thread_local variable {} needs C11 or later", node.var_name)));
+        }
+
+        if node.thread_local && block_scope && node.storage_class.is_none() {
+            return Err(JasmineError::invalid_node(format!("The _Thread_local variable {} must also be static or extern inside a function", node.var_name)));
+        }
+
+        let initialized = node.var_value.is_some() || node.declarators.iter().any(|declarator| declarator.value.is_some());
+
+        if node.storage_class == Some(CStorageClass::Extern) && block_scope && initialized {
+            return Err(JasmineError::invalid_node(format!("The extern variable {} cannot be initialized inside a function", node.var_name)));
+        }
+
+        let mut code = String::new();
+
+        if let Some(storage_class) = node.storage_class {
+            code.push_str(format!("{} ", storage_class).as_str());
+        }
+
+        if node.thread_local {
+            code.push_str("_Thread_local ");
+        }
+
+        code.push_str(self.generate_c_declarator(node, &node.var_name, node.var_value.as_ref(), true)?.as_str());
+
+        for declarator in &node.declarators {
+            let declarator_code = self.generate_c_declarator(node, &declarator.name, declarator.value.as_ref(), false)
+                .map_err(|error| error.in_node(format!("declarator {}", declarator.name)))?;
+
+            code.push_str(", ");
+            code.push_str(declarator_code.as_str());
+        }
+
+        Ok(code)
+    }
+
+    /* Generates one declarator of a variable declaration with its initializer (x = 21), checking the initializer against the declared type */
+    fn generate_c_declarator(&self, node: &CVariableStatement, name: &str, value: Option<&CExpression>, with_specifier: bool) -> JasmineResult<String> {
+        self.check_array_sizes(node, name, &self.resolve_type(&node.var_type))?;
+
+        match (self.resolve_type(&node.var_type), value) {
+            (CType::Array(element, size), Some(value)) => self.check_array_initializer(name, &element, &size, value)?,
+
+            // int numbers[]; (only the declaration of an array defined elsewhere can leave out its size)
+            (CType::Array(_, CArraySize::Unspecified), None) if node.storage_class != Some(CStorageClass::Extern) => {
+                return Err(JasmineError::invalid_node(format!("The array {} needs a size or an initializer", name)));
             }
 
-            _ => { todo!() }
+            // A literal 0 is a null pointer constant (int *p = 0;):
+            (var_type, Some(CExpression::NumberExpression(number))) if var_type.is_pointer() && number.cvalue == 0 => {}
+
+            (var_type, Some(value)) => match self.expression_type(value).map(|node_type| (self.resolve_type(&node_type), node_type)) {
+                // Any pointer converts to _Bool, true unless it is null (bool valid = p;):
+                Some((resolved, _)) if *var_type.unqualified() == CType::_Bool && resolved.is_pointer() => {}
+
+                Some((resolved, node_type)) if !self.is_assignable(&var_type, &resolved) => {
+                    return Err(JasmineError::type_mismatch(format!("The type {} does not match with the type {}", node.var_type, node_type)));
+                }
+
+                _ => {}
+            },
+
+            _ => {}
         }
+
+        let declarator = if with_specifier {
+            node.var_type.declaration(name)
+        } else {
+            node.var_type.declarator(name)
+        };
+
+        let value = match value {
+            // A plain initializer list reads better than a compound literal in a declaration:
+            Some(CExpression::StructExpression(expr)) => self.generate_c_struct_initializer(expr)?,
+            Some(value) => self.generate_expression(value)?,
+            None => return Ok(declarator),
+        };
+
+        Ok(format!("{} = {}", declarator, value))
     }
 
-    pub fn generate_c_number_expression(&self, expr: &CNumberExpression) -> String {
-        format!("{}", expr.cvalue)
+    /* Whether a string literal with `prefix` can initialize an array of `element` (char s[] = "hi", wchar_t w[] = L"hi") */
+    fn is_string_element(&self, element: &CType, prefix: CStringPrefix) -> bool {
+        let element = self.resolve_type(element);
+        let element = element.unqualified();
+
+        match prefix {
+            CStringPrefix::None | CStringPrefix::Utf8 => {
+                matches!(element, CType::Char) || matches!(element, CType::Signed(ctype) | CType::Unsigned(ctype) if **ctype == CType::Char)
+            }
+            prefix => *element == self.resolve_type(&prefix.element_type()),
+        }
     }
 
-    pub fn generate_c_string_expression(&self, expr: &CStringExpression) -> String {
-        format!("\"{}\"", expr.cvalue)
+    /* Checks an array initializer's element type and count against the declared array type */
+    /* Checks the array sizes in the type of a declared variable: no array is empty,
+       and variable lengths are integers that only automatic variables can have (int values[count];) */
+    fn check_array_sizes(&self, node: &CVariableStatement, name: &str, ctype: &CType) -> JasmineResult<()> {
+        match ctype {
+            CType::Array(element, size) => {
+                match size {
+                    CArraySize::Constant(0) => {
+                        return Err(JasmineError::invalid_node(format!("The array {} cannot have a size of 0", name)));
+                    }
+
+                    CArraySize::Variable(size) => {
+                        if self.printer.depth() == 0 || node.storage_class.is_some() {
+                            return Err(JasmineError::invalid_node(format!("The variable-length array {} can only be an automatic variable inside a function", name)));
+                        }
+
+                        if let Some(size_type) = self.expression_type(size).map(|size_type| self.resolve_type(&size_type)) {
+                            if !size_type.is_integer() && !size_type.has_typedef_name() {
+                                return Err(JasmineError::type_mismatch(format!("The size of the array {} has non-integer type {}", name, size_type)));
+                            }
+                        }
+
+                        self.generate_expression(size).map_err(|error| error.in_node("array size".to_string()))?;
+                    }
+
+                    _ => {}
+                }
+
+                self.check_array_sizes(node, name, element)
+            }
+
+            CType::Pointer(inner) | CType::Const(inner) | CType::Volatile(inner) | CType::Restrict(inner) => self.check_array_sizes(node, name, inner),
+            _ => Ok(()),
+        }
     }
 
-    pub fn generate_c_variable_statement(&self, node: &CVariableStatement) -> String {
-        match node.var_value.get_type() {
-            Some(node_type) => {
-                if &node.var_type != node_type {
-                    // TODO: use thiserror for error
-                    panic!("The type {} does not match with the type {}", node.var_type, node_type);
+    /* Whether a value of resolved type `from` can initialize an object of resolved type `to`.
+       Typedef names the program does not define (wchar_t, time_t, ...) are unknown types, so they are not checked */
+    fn is_assignable(&self, to: &CType, from: &CType) -> bool {
+        to.has_typedef_name() || from.has_typedef_name() || to.is_assignable_from(from)
+    }
+
+    fn check_array_initializer(&self, name: &str, element: &CType, size: &CArraySize, value: &CExpression) -> JasmineResult<()> {
+        let length = match value {
+            CExpression::ArrayExpression(array) => {
+                match self.resolve_type(element) {
+                    // int m[2][3] = { { 1, 2, 3 }, { 4, 5, 6 } };
+                    CType::Array(inner_element, inner_size) => {
+                        for (index, item) in array.cvalue.iter().enumerate() {
+                            self.check_array_initializer(name, &inner_element, &inner_size, item)
+                                .map_err(|error| error.in_node(format!("element {}", index + 1)))?;
+                        }
+                    }
+
+                    element_type if !self.is_assignable(&element_type, &self.resolve_type(&array.ctype)) => {
+                        return Err(JasmineError::type_mismatch(format!("The array {} has elements of type {}, but is initialized with elements of type {}", name, element, array.ctype)));
+                    }
+
+                    _ => {}
                 }
+
+                array.length
+            }
+
+            // char s[6] = "hello"; (C allows dropping the terminating null character when the array is exactly full)
+            CExpression::StringExpression(string) if self.is_string_element(element, string.prefix) => string.length,
+
+            _ => {
+                return Err(JasmineError::type_mismatch(format!("The array {} must be initialized with an array or string literal", name)));
             }
+        };
+
+        match size {
+            CArraySize::Constant(size) if length > *size => {
+                Err(JasmineError::type_mismatch(format!("The array {} has {} elements, but is initialized with {}", name, size, length)))
+            }
+
+            CArraySize::Variable(_) => {
+                Err(JasmineError::invalid_node(format!("The variable-length array {} cannot be initialized", name)))
+            }
+
+            _ => Ok(()),
+        }
+    }
 
-            None => {}
+    pub fn generate_c_assignment_statement(&self, node: &CAssignmentStatement) -> JasmineResult<String> {
+        if !node.target.is_lvalue() {
+            return Err(JasmineError::invalid_node(format!("The assignment target {} is not an lvalue", self.generate_expression(&node.target)?)));
         }
 
-        format!("{INDENT}{} {} = {};\n", node.var_type, node.var_name, self.generate_expression(&node.var_value))
+        Ok(format!(
+            "{}{} {} {};\n",
+            self.printer.indent(),
+            self.generate_expression(&node.target)?,
+            node.operator,
+            self.generate_expression(&node.value)?
+        ))
     }
 
-    pub fn generate_c_assignment_statement(&self, node: &CAssignmentStatement) -> String {
-        format!("{INDENT}{} = {};\n", node.var_name, self.generate_expression(&node.var_value))
+    pub fn generate_c_return_statement(&self, node: &CReturnStatement) -> JasmineResult<String> {
+        Ok(format!("{}return {};\n", self.printer.indent(), self.generate_expression(&node.value)?))
     }
 
-    pub fn generate_c_return_statement(&self, node: &CReturnStatement) -> String {
-        format!("{INDENT}return {};", self.generate_expression(&node.value))
+    pub fn generate_c_function_call_statement(&self, expr: &CFunctionCallStatement) -> JasmineResult<String> {
+        let head = format!("{}(", self.generate_c_postfix_operand(&expr.function)?);
+        let args = self.generate_c_function_call_args(expr)?;
+
+        Ok(format!("{}{}\n", self.printer.indent(), self.printer.wrap_list(&head, &args, ");")))
+    }
+
+    pub fn generate_c_function_call_expression(&self, expr: &CFunctionCallStatement) -> JasmineResult<String> {
+        Ok(format!("{}({})", self.generate_c_postfix_operand(&expr.function)?, self.generate_c_function_call_args(expr)?.join(", ")))
     }
 
-    pub fn generate_c_function_call_statement(&self, expr: &CFunctionCallStatement) -> String {
-        format!("{INDENT}{}({});\n", expr.function, expr.args.iter().map(|x| self.generate_expression(x)).collect::<Vec<_>>().join(", "))
+    fn generate_c_function_call_args(&self, expr: &CFunctionCallStatement) -> JasmineResult<Vec<String>> {
+        expr.args.iter().enumerate()
+            .map(|(index, arg)| self.generate_expression(arg).map_err(|error| error.in_node(format!("argument {}", index + 1))))
+            .collect()
     }
 
     pub fn generate_include_statement(&self, node: &CIncludeStatement) -> String {
         let library = &node.library;
 
         if library.lib_link {
-            format!("#include <{}>", library.lib_name)
+            self.generate_directive(format!("#include <{}>", library.lib_name))
+        } else {
+            self.generate_directive(format!("#include \"{}\"", library.lib_name))
+        }
+    }
+
+    pub fn generate_typedef_statement(&self, node: &CTypedefStatement) -> JasmineResult<String> {
+        self.check_type(&node.ctype)?;
+
+        // A typedef name is left unresolved only when it is not defined, or when it is part of a cycle:
+        if let CType::CustomTypedefType(name) = self.resolve_type(&CType::CustomTypedefType(node.name.clone())) {
+            let defined = self.find_statement(|statement| match statement {
+                CStatement::TypedefStatement(typedef) if typedef.name == name => Some(()),
+                _ => None,
+            });
+
+            if defined.is_some() {
+                return Err(JasmineError::invalid_node(format!("The typedef {} is defined in terms of itself", node.name)));
+            }
+        }
+
+        Ok(self.generate_declaration(format!("typedef {};", node.ctype.declaration(&node.name))))
+    }
+
+    pub fn generate_define_statement(&self, node: &CDefineStatement) -> String {
+        let params = match &node.params {
+            Some(params) => {
+                let mut params = params.clone();
+
+                if node.variadic {
+                    params.push("...".to_string());
+                }
+
+                format!("({})", params.join(", "))
+            }
+
+            None => String::new(),
+        };
+
+        let body = node.body.lines().collect::<Vec<_>>().join(" \\\n");
+
+        if body.is_empty() {
+            self.generate_directive(format!("#define {}{}", node.name, params))
+        } else {
+            self.generate_directive(format!("#define {}{} {}", node.name, params, body))
+        }
+    }
+
+    pub fn generate_conditional_statement(&self, node: &CConditionalStatement) -> JasmineResult<String> {
+        let generate_block = |block: &Vec<CProgramNode>, branch: &str| -> JasmineResult<String> {
+            block.iter().map(|node| self.generate_program_node(node)).collect::<JasmineResult<String>>()
+                .map_err(|error| error.in_node(branch.to_string()))
+        };
+
+        let mut code = match &node.condition {
+            CPreprocessorCondition::If(condition) => self.generate_directive(format!("#if {}", condition)),
+            CPreprocessorCondition::Ifdef(name) => self.generate_directive(format!("#ifdef {}", name)),
+            CPreprocessorCondition::Ifndef(name) => self.generate_directive(format!("#ifndef {}", name)),
+        };
+
+        code.push_str(generate_block(&node.block, "#if block")?.as_str());
+
+        for (index, (condition, block)) in node.elif_blocks.iter().enumerate() {
+            code.push_str(self.generate_directive(format!("#elif {}", condition)).as_str());
+            code.push_str(generate_block(block, &format!("#elif block {}", index + 1))?.as_str());
+        }
+
+        if let Some(else_block) = &node.else_block {
+            code.push_str(self.generate_directive("#else".to_string()).as_str());
+            code.push_str(generate_block(else_block, "#else block")?.as_str());
+        }
+
+        code.push_str(self.generate_directive("#endif".to_string()).as_str());
+        Ok(code)
+    }
+
+    /* Places a preprocessor directive on its own line, in column 0 like every directive.
+       At file scope it starts a new line like the other top-level nodes, inside a block it ends its line like a statement */
+    fn generate_directive(&self, directive: String) -> String {
+        if self.printer.depth() == 0 {
+            format!("\n{}", directive)
+        } else {
+            format!("{}\n", directive)
+        }
+    }
+
+    /* Places a declaration that may appear at file scope or in a block (typedef, struct, function prototype, ...).
+       At file scope it starts a new line like the other top-level nodes, inside a block it is indented and ends its line like a statement */
+    fn generate_declaration(&self, declaration: String) -> String {
+        if self.printer.depth() == 0 {
+            format!("\n{}", declaration)
+        } else {
+            format!("{}{}\n", self.printer.indent(), declaration)
+        }
+    }
+
+    pub fn generate_block_statement(&self, block_node: &CBlockStatement) -> JasmineResult<String> {
+        let mut code = String::new();
+
+        for (index, node) in block_node.block.iter().enumerate() {
+            match node {
+                CStatement::LabelStatement(label) => {
+                    // A label must be followed by a statement, and a declaration is not one:
+                    let needs_null_statement = matches!(
+                        block_node.block.get(index + 1),
+                        None | Some(CStatement::VariableStatement(_))
+                    );
+
+                    code.push_str(self.generate_label_statement(label, needs_null_statement).as_str());
+                }
+
+                _ => {
+                    let statement = self.generate_statement(node)
+                        .map_err(|error| error.in_node(format!("statement {} ({})", index + 1, node.describe())))?;
+                    code.push_str(statement.as_str());
+                }
+            }
+        }
+
+        Ok(code)
+    }
+
+    /* Generates a block with its braces, nested in the statement at the current depth that owns it */
+    pub fn generate_braced_block_statement(&self, kind: CBlockKind, block_node: &CBlockStatement) -> JasmineResult<String> {
+        self.generate_braced(kind, || self.generate_block_statement(block_node))
+    }
+
+    fn generate_braced(&self, kind: CBlockKind, generate: impl FnOnce() -> JasmineResult<String>) -> JasmineResult<String> {
+        Ok(format!(
+            "{}{}{}",
+            self.printer.open_brace(kind),
+            self.printer.nested(self.printer.block_levels(kind), generate)?,
+            self.printer.close_brace(kind)
+        ))
+    }
+
+    pub fn generate_if_statement(&self, node: &CIfStatement) -> JasmineResult<String> {
+        let mut code = format!(
+            "{}if ({}){}",
+            self.printer.indent(),
+            self.generate_expression(&node.condition).map_err(|error| error.in_node("condition".to_string()))?,
+            self.generate_braced_block_statement(CBlockKind::Control, &node.then_block)
+                .map_err(|error| error.in_node("then block".to_string()))?
+        );
+
+        for (index, else_if) in node.else_if_blocks.iter().enumerate() {
+            let generate_else_if = || -> JasmineResult<String> {
+                Ok(format!(
+                    "{} if ({}){}",
+                    self.printer.after_close_brace("else"),
+                    self.generate_expression(&else_if.condition)?,
+                    self.generate_braced_block_statement(CBlockKind::Control, &else_if.block)?
+                ))
+            };
+
+            code.push_str(generate_else_if().map_err(|error| error.in_node(format!("else if block {}", index + 1)))?.as_str());
+        }
+
+        if let Some(else_block) = &node.else_block {
+            code.push_str(format!(
+                "{}{}",
+                self.printer.after_close_brace("else"),
+                self.generate_braced_block_statement(CBlockKind::Control, else_block)
+                    .map_err(|error| error.in_node("else block".to_string()))?
+            ).as_str());
+        }
+
+        code.push('\n');
+        Ok(code)
+    }
+
+    /* Generates the braced body of a loop, allowing `break` and `continue` inside it */
+    pub fn generate_loop_block_statement(&self, block_node: &CBlockStatement) -> JasmineResult<String> {
+        self.loop_depth.set(self.loop_depth.get() + 1);
+        // The counter is restored before the error is returned, so a failed loop does not leak into later statements:
+        let code = self.generate_braced_block_statement(CBlockKind::Control, block_node);
+        self.loop_depth.set(self.loop_depth.get() - 1);
+
+        code.map_err(|error| error.in_node("body".to_string()))
+    }
+
+    pub fn generate_while_statement(&self, node: &CWhileStatement) -> JasmineResult<String> {
+        Ok(format!(
+            "{}while ({}){}\n",
+            self.printer.indent(),
+            self.generate_expression(&node.condition).map_err(|error| error.in_node("condition".to_string()))?,
+            self.generate_loop_block_statement(&node.block)?
+        ))
+    }
+
+    pub fn generate_do_while_statement(&self, node: &CDoWhileStatement) -> JasmineResult<String> {
+        Ok(format!(
+            "{}do{}{} ({});\n",
+            self.printer.indent(),
+            self.generate_loop_block_statement(&node.block)?,
+            self.printer.after_close_brace("while"),
+            self.generate_expression(&node.condition).map_err(|error| error.in_node("condition".to_string()))?
+        ))
+    }
+
+    pub fn generate_for_statement(&self, node: &CForStatement) -> JasmineResult<String> {
+        let init = match &node.init {
+            Some(CForInit::VariableStatement(stmt)) => self.generate_c_variable_declaration(stmt),
+            Some(CForInit::Expression(expr)) => self.generate_expression(expr),
+            None => Ok(String::new()),
+        }.map_err(|error| error.in_node("initializer".to_string()))?;
+
+        let condition = match &node.condition {
+            Some(expr) => format!(" {}", self.generate_expression(expr).map_err(|error| error.in_node("condition".to_string()))?),
+            None => String::new(),
+        };
+
+        let step = match &node.step {
+            Some(expr) => format!(" {}", self.generate_expression(expr).map_err(|error| error.in_node("step".to_string()))?),
+            None => String::new(),
+        };
+
+        Ok(format!(
+            "{}for ({};{};{}){}\n",
+            self.printer.indent(),
+            init,
+            condition,
+            step,
+            self.generate_loop_block_statement(&node.block)?
+        ))
+    }
+
+    pub fn generate_switch_statement(&self, node: &CSwitchStatement) -> JasmineResult<String> {
+        let mut case_values = Vec::new();
+        let head = format!(
+            "{}switch ({})",
+            self.printer.indent(),
+            self.generate_expression(&node.condition).map_err(|error| error.in_node("condition".to_string()))?
+        );
+
+        self.switch_depth.set(self.switch_depth.get() + 1);
+
+        // Case labels are nested one level inside the switch, and their statements one more:
+        let body = self.generate_braced(CBlockKind::Control, || {
+            let mut code = String::new();
+
+            for (index, case) in node.cases.iter().enumerate() {
+                let mut generate_case = || -> JasmineResult<String> {
+                    let case_value = self.get_case_value(&case.value)?;
+
+                    if case_values.contains(&case_value) {
+                        return Err(JasmineError::invalid_node(format!("Duplicate case value {} in switch statement", self.generate_expression(&case.value)?)));
+                    }
+
+                    case_values.push(case_value);
+
+                    let label = format!("case {}:", self.generate_expression(&case.value)?);
+                    let last = index + 1 == node.cases.len() && node.default_block.is_none();
+                    self.generate_switch_arm(&label, &case.block, case.fallthrough, last)
+                };
+
+                code.push_str(generate_case().map_err(|error| error.in_node(format!("case {}", index + 1)))?.as_str());
+            }
+
+            if let Some(default_block) = &node.default_block {
+                code.push_str(self.generate_switch_arm("default:", default_block, false, true).map_err(|error| error.in_node("default".to_string()))?.as_str());
+            }
+
+            Ok(code)
+        });
+
+        // Restored before the error is returned, like the loop counter:
+        self.switch_depth.set(self.switch_depth.get() - 1);
+
+        Ok(format!("{}{}\n", head, body?))
+    }
+
+    /* Generates a labeled arm of a switch, with a `break;` unless it falls through or already jumps away */
+    fn generate_switch_arm(&self, label: &str, block: &CBlockStatement, fallthrough: bool, last: bool) -> JasmineResult<String> {
+        let jumps_away = matches!(
+            block.block.last(),
+            Some(CStatement::BreakStatement | CStatement::ContinueStatement | CStatement::ReturnStatement(_) | CStatement::GotoStatement(_))
+        );
+
+        let generate_body = || -> JasmineResult<String> {
+            let mut body = self.generate_block_statement(block)?;
+
+            if !fallthrough && !jumps_away {
+                body.push_str(format!("{}break;\n", self.printer.indent()).as_str());
+            } else if last && block.block.is_empty() {
+                // A label cannot end a compound statement before C23, so the last arm gets a null statement:
+                body.push_str(format!("{};\n", self.printer.indent()).as_str());
+            }
+
+            Ok(body)
+        };
+
+        // Declarations directly after a case label need their own scope:
+        if block.block.iter().any(|statement| matches!(statement, CStatement::VariableStatement(_))) {
+            Ok(format!("{}{}{}\n", self.printer.indent(), label, self.generate_braced(CBlockKind::CaseLabel, generate_body)?))
         } else {
-            format!("#include \"{}\"", library.lib_name)
+            Ok(format!("{}{}\n{}", self.printer.indent(), label, self.printer.nested(1, generate_body)?))
+        }
+    }
+
+    /* Evaluates a case label to a comparable value, resolving enum constants from the enum definitions */
+    fn get_case_value(&self, expr: &CExpression) -> JasmineResult<CCaseValue> {
+        match expr {
+            CExpression::NumberExpression(number) => Ok(CCaseValue::Integer(number.cvalue as i128)),
+
+            CExpression::CharExpression(char) => Ok(CCaseValue::Integer(char.cvalue as i128)),
+
+            CExpression::UnaryExpression(CUnaryExpression { operator: CUnaryOperator::Negate, operand: CUnaryOperand::Expression(operand) }) => {
+                match self.get_case_value(operand)? {
+                    CCaseValue::Integer(value) => Ok(CCaseValue::Integer(-value)),
+                    CCaseValue::Constant(name) => Ok(CCaseValue::Constant(format!("-{}", name))),
+                }
+            }
+
+            CExpression::IdentifierExpression(identifier) => {
+                let value = self.find_statement(|statement| match statement {
+                    CStatement::EnumDefinition(definition) => definition.get_value(&identifier.cvalue),
+                    _ => None,
+                });
+
+                match value {
+                    Some(value) => Ok(CCaseValue::Integer(value as i128)),
+                    None => Ok(CCaseValue::Constant(identifier.cvalue.clone())),
+                }
+            }
+
+            _ => {
+                Err(JasmineError::unsupported_node(format!("The case label {} is not an integer, char or enum constant", self.generate_expression(expr)?)))
+            }
+        }
+    }
+
+    pub fn generate_break_statement(&self) -> JasmineResult<String> {
+        if self.loop_depth.get() == 0 && self.switch_depth.get() == 0 {
+            return Err(JasmineError::invalid_node("`break` statement not within a loop or switch".to_string()));
         }
+
+        Ok(format!("{}break;\n", self.printer.indent()))
+    }
+
+    pub fn generate_continue_statement(&self) -> JasmineResult<String> {
+        if self.loop_depth.get() == 0 {
+            return Err(JasmineError::invalid_node("`continue` statement not within a loop".to_string()));
+        }
+
+        Ok(format!("{}continue;\n", self.printer.indent()))
     }
 
-    pub fn generate_block_statement(&self, block_node: &CBlockStatement) -> String {
+    /* Generates a label one level less indented than the statements around it */
+    pub fn generate_label_statement(&self, label: &str, needs_null_statement: bool) -> String {
+        let indent = self.printer.indent_at(self.printer.depth().saturating_sub(1));
+
+        if needs_null_statement {
+            format!("{indent}{}:;\n", label)
+        } else {
+            format!("{indent}{}:\n", label)
+        }
+    }
+
+    /* Checks that labels are declared once per function and that every goto targets one of them */
+    fn check_function_labels(&self, node: &CFunctionStatement) -> JasmineResult<()> {
+        let mut labels = Vec::new();
+        let mut gotos = Vec::new();
+
+        let Some(function_block) = &node.function_block else {
+            return Ok(());
+        };
+
+        function_block.walk(&mut |statement| match statement {
+            CStatement::LabelStatement(label) => labels.push(label),
+            CStatement::GotoStatement(label) => gotos.push(label),
+            _ => {}
+        });
+
+        for (index, label) in labels.iter().enumerate() {
+            if labels[..index].contains(label) {
+                return Err(JasmineError::invalid_node(format!("The label {} is declared more than once in function {}", label, node.function_name)));
+            }
+        }
+
+        for goto in gotos {
+            if !labels.contains(&goto) {
+                return Err(JasmineError::undefined_identifier(goto, format!("The label {} used by goto is not declared in function {}", goto, node.function_name)));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn generate_function_statement(&self, node: &CFunctionStatement) -> JasmineResult<String> {
+        self.check_function_labels(node)?;
+
+        let Some(function_block) = &node.function_block else {
+            return self.generate_function_prototype(node);
+        };
+
+        // Nested functions are a GNU extension, a block can only declare a function:
+        if self.printer.depth() > 0 {
+            return Err(JasmineError::unsupported_node(format!("The function {} can only be defined at file scope", node.function_name)));
+        }
+
+        // An attached brace counts towards the width of the last line of the header:
+        let header = if self.printer.brace_on_own_line(CBlockKind::Function) {
+            self.generate_function_header(node, "")?
+        } else {
+            self.generate_function_header(node, " {")?.trim_end_matches(" {").to_string()
+        };
+
+        Ok(format!("\n{}{}", header, self.generate_braced_block_statement(CBlockKind::Function, function_block)?))
+    }
+
+    /* Generates the declaration of a function without its body (int add(int a, int b);) */
+    pub fn generate_function_prototype(&self, node: &CFunctionStatement) -> JasmineResult<String> {
+        Ok(self.generate_declaration(self.generate_function_header(node, ";")?))
+    }
+
+    /* Generates the storage class, specifiers, return type and parameters of a function (static inline int add(int a, int b)),
+       followed by `suffix` */
+    fn generate_function_header(&self, node: &CFunctionStatement, suffix: &str) -> JasmineResult<String> {
+        self.check_type(&node.function_type).map_err(|error| error.in_node("return type".to_string()))?;
+
+        for arg in &node.function_args {
+            self.check_type(&arg.r#type).map_err(|error| error.in_node(format!("parameter {}", arg.name)))?;
+        }
+
+        let mut params = node.function_args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        if node.variadic {
+            if params.is_empty() {
+                return Err(JasmineError::invalid_node(format!("The variadic function {} needs at least one named parameter before ...", node.function_name)));
+            }
+
+            params.push("...".to_string());
+        } else if params.is_empty() {
+            // An empty list would leave the parameters unspecified before C23:
+            params.push("void".to_string());
+        }
+
+        let mut header = String::new();
+
+        if let Some(storage_class) = node.storage_class {
+            header.push_str(format!("{} ", storage_class).as_str());
+        }
+
+        // Function specifiers are only hints, so the ones the target standard lacks are left out:
+        for specifier in node.specifiers.iter().filter(|specifier| self.standard >= specifier.standard()) {
+            header.push_str(format!("{} ", specifier).as_str());
+        }
+
+        // The parameters sit inside the declarator (int (*handler(int signal))(int)), so they are placed after rendering it:
+        let declaration = node.function_type.declaration(&format!("{}(\0)", node.function_name));
+        let (head, rest) = declaration.split_once("\0)").unwrap_or((&declaration, ""));
+
+        Ok(self.printer.wrap_list(&(header + head), &params, &format!("){}{}", rest, suffix)))
+    }
+
+    pub fn generate_struct_definition(&self, node: &CStructDefinition) -> JasmineResult<String> {
+        Ok(self.generate_declaration(format!("struct {}{};", node.name, self.generate_braced(CBlockKind::Definition, || self.generate_struct_fields(&node.fields))?)))
+    }
+
+    pub fn generate_union_definition(&self, node: &CUnionDefinition) -> JasmineResult<String> {
+        Ok(self.generate_declaration(format!("union {}{};", node.name, self.generate_braced(CBlockKind::Definition, || self.generate_struct_fields(&node.fields))?)))
+    }
+
+    fn generate_struct_fields(&self, fields: &[CStructField]) -> JasmineResult<String> {
+        let indent = self.printer.indent();
         let mut code = String::new();
 
-        for node in &block_node.block {
-            code.push_str(self.generate_statement(node).as_str());
+        for field in fields {
+            self.check_type(&field.r#type).map_err(|error| error.in_node(format!("field {}", field.name)))?;
+
+            match field.bit_width {
+                Some(bit_width) => {
+                    if !field.r#type.is_integer() {
+                        return Err(JasmineError::type_mismatch(format!("The bit-field {} has non-integer type {}", field.name, field.r#type))
+                            .in_node(format!("field {}", field.name)));
+                    }
+
+                    code.push_str(format!("{indent}{} : {};\n", field.r#type.declaration(&field.name), bit_width).as_str());
+                }
+
+                None => code.push_str(format!("{indent}{};\n", field.r#type.declaration(&field.name)).as_str()),
+            }
         }
 
-        code
+        Ok(code)
     }
 
-    pub fn generate_function_statement(&self, node: &CFunctionStatement) -> String {
-        format!(r#"
-{} {}({}) {{
-{}
-}}"#, node.function_type, node.function_name, node.function_args
-            .iter().map(|arg| arg.to_string())
-            .collect::<Vec<_>>()
-            .join(", "), self.generate_block_statement(&node.function_block))
+    pub fn generate_enum_definition(&self, node: &CEnumDefinition) -> JasmineResult<String> {
+        let body = self.generate_braced(CBlockKind::Definition, || {
+            let indent = self.printer.indent();
+            let enumerators = node.enumerators.iter().map(|enumerator| match enumerator.value {
+                Some(value) => format!("{indent}{} = {}", enumerator.name, value),
+                None => format!("{indent}{}", enumerator.name),
+            }).collect::<Vec<_>>().join(",\n");
+
+            Ok(format!("{}\n", enumerators))
+        })?;
+
+        Ok(self.generate_declaration(format!("enum {}{};", node.name, body)))
     }
 
     pub fn get_c_file(&self) -> CFile {
@@ -0,0 +1,110 @@
+// ©2025 - BestJasmine - BestMat - All rights reserved.
+
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::io;
+use thiserror::Error;
+
+pub type JasmineResult<T> = Result<T, JasmineError>;
+
+/* Where a node sits in the C Program, from the top-level node down to the node itself */
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CNodePath {
+    pub nodes: Vec<String>,             // ["function main", "statement 2 (if statement)", "else block"]
+}
+
+impl Display for CNodePath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.nodes.is_empty() {
+            return Ok(());
+        }
+
+        write!(f, " in {}", self.nodes.join(" > "))
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum JasmineError {
+    #[error("type mismatch{path}: {message}")]
+    TypeMismatch {                      // int x = "text";
+        path: CNodePath,
+        message: String,
+    },
+
+    #[error("unsupported node{path}: {message}")]
+    UnsupportedNode {                   // return 0; at file scope, case x + 1:
+        path: CNodePath,
+        message: String,
+    },
+
+    #[error("undefined identifier `{name}`{path}: {message}")]
+    UndefinedIdentifier {               // goto missing;, (struct Point){ .z = 1 }
+        path: CNodePath,
+        name: String,
+        message: String,
+    },
+
+    #[error("invalid node{path}: {message}")]
+    InvalidNode {                       // break; outside a loop, duplicate case values
+        path: CNodePath,
+        message: String,
+    },
+
+    #[error("failed to write {file}: {source}")]
+    Io {
+        file: String,
+        source: io::Error,
+    },
+}
+
+impl JasmineError {
+    pub fn type_mismatch(message: String) -> Self {
+        JasmineError::TypeMismatch { path: CNodePath::default(), message }
+    }
+
+    pub fn unsupported_node(message: String) -> Self {
+        JasmineError::UnsupportedNode { path: CNodePath::default(), message }
+    }
+
+    pub fn undefined_identifier(name: &str, message: String) -> Self {
+        JasmineError::UndefinedIdentifier { path: CNodePath::default(), name: name.to_string(), message }
+    }
+
+    pub fn invalid_node(message: String) -> Self {
+        JasmineError::InvalidNode { path: CNodePath::default(), message }
+    }
+
+    pub fn io(file: &str, source: io::Error) -> Self {
+        JasmineError::Io { file: file.to_string(), source }
+    }
+
+    /* Adds the node that contains the offending node to the front of the path, as the error travels up the tree */
+    pub fn in_node(mut self, node: String) -> Self {
+        if let Some(path) = self.path_mut() {
+            path.nodes.insert(0, node);
+        }
+
+        self
+    }
+
+    /* The path to the offending node (None for I/O errors) */
+    pub fn path(&self) -> Option<&CNodePath> {
+        match self {
+            JasmineError::TypeMismatch { path, .. }
+            | JasmineError::UnsupportedNode { path, .. }
+            | JasmineError::UndefinedIdentifier { path, .. }
+            | JasmineError::InvalidNode { path, .. } => Some(path),
+            JasmineError::Io { .. } => None,
+        }
+    }
+
+    fn path_mut(&mut self) -> Option<&mut CNodePath> {
+        match self {
+            JasmineError::TypeMismatch { path, .. }
+            | JasmineError::UnsupportedNode { path, .. }
+            | JasmineError::UndefinedIdentifier { path, .. }
+            | JasmineError::InvalidNode { path, .. } => Some(path),
+            JasmineError::Io { .. } => None,
+        }
+    }
+}
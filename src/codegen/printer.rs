@@ -0,0 +1,187 @@
+// ©2025 - BestJasmine - BestMat - All rights reserved.
+
+use std::cell::Cell;
+
+/* Placement of the braces around a block */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CBraceStyle {
+    Attach,                             // int main() {, if (x) {
+    KAndR,                              // int main()\n{, if (x) {
+    Allman,                             // int main()\n{, if (x)\n{
+    #[allow(clippy::upper_case_acronyms)]
+    GNU,                                // int main()\n{, if (x)\n    {  (the braces of control statements are indented)
+}
+
+/* The construct that owns a block, which decides where its braces go */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CBlockKind {
+    Function,                           // int main() { ... }
+    Definition,                         // struct Point { ... };
+    Control,                            // if, else, while, do, for, switch
+    CaseLabel,                          // case 1: { ... }
+}
+
+/* Layout of the generated code: indentation, braces and line width, plus the nesting depth while generating */
+#[derive(Debug, Clone)]
+pub struct CPrinter {
+    pub indent_width: usize,            // Columns per nesting level
+    pub use_tabs: bool,                 // Indent with one tab per level (alignment still uses spaces)
+    pub brace_style: CBraceStyle,
+    pub max_line_width: usize,          // Calls and parameter lists longer than this are wrapped
+    depth: Cell<usize>,                 // Nesting depth of the statements being generated (0 at file scope)
+}
+
+impl CPrinter {
+    pub fn new() -> Self {
+        Self {
+            indent_width: 4,
+            use_tabs: false,
+            brace_style: CBraceStyle::Attach,
+            max_line_width: 80,
+            depth: Cell::new(0),
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth.get()
+    }
+
+    /* Runs `generate` with the statements nested `levels` deeper */
+    pub fn nested<T>(&self, levels: usize, generate: impl FnOnce() -> T) -> T {
+        self.depth.set(self.depth.get() + levels);
+        let code = generate();
+        self.depth.set(self.depth.get() - levels);
+
+        code
+    }
+
+    /* The indentation of a statement at the current nesting depth */
+    pub fn indent(&self) -> String {
+        self.indent_at(self.depth.get())
+    }
+
+    pub fn indent_at(&self, depth: usize) -> String {
+        if self.use_tabs {
+            "\t".repeat(depth)
+        } else {
+            " ".repeat(depth * self.indent_width)
+        }
+    }
+
+    /* How many levels deeper than its owner the statements of a block are */
+    pub fn block_levels(&self, kind: CBlockKind) -> usize {
+        match (self.brace_style, kind) {
+            (CBraceStyle::GNU, CBlockKind::Control | CBlockKind::CaseLabel) => 2,
+            _ => 1,
+        }
+    }
+
+    pub fn brace_on_own_line(&self, kind: CBlockKind) -> bool {
+        match (self.brace_style, kind) {
+            (CBraceStyle::Attach, _) => false,
+            (CBraceStyle::KAndR, kind) => kind == CBlockKind::Function,
+            (CBraceStyle::Allman | CBraceStyle::GNU, _) => true,
+        }
+    }
+
+    fn brace_indent(&self, kind: CBlockKind) -> String {
+        self.indent_at(self.depth.get() + self.block_levels(kind) - 1)
+    }
+
+    /* The opening brace of a block owned by a statement at the current depth, after its head (` {\n` or `\n{\n`) */
+    pub fn open_brace(&self, kind: CBlockKind) -> String {
+        if self.brace_on_own_line(kind) {
+            format!("\n{}{{\n", self.brace_indent(kind))
+        } else {
+            " {\n".to_string()
+        }
+    }
+
+    /* The closing brace of a block owned by a statement at the current depth, without a line break */
+    pub fn close_brace(&self, kind: CBlockKind) -> String {
+        format!("{}}}", self.brace_indent(kind))
+    }
+
+    /* Continues a statement after the closing brace of one of its blocks (`} else`, `}\nwhile`) */
+    pub fn after_close_brace(&self, keyword: &str) -> String {
+        let own_line = match self.brace_style {
+            CBraceStyle::Attach | CBraceStyle::KAndR => false,
+            CBraceStyle::Allman => keyword != "while",
+            CBraceStyle::GNU => true,
+        };
+
+        if own_line {
+            format!("\n{}{}", self.indent(), keyword)
+        } else {
+            format!(" {}", keyword)
+        }
+    }
+
+    /* Width of a line that starts at the current depth */
+    pub fn line_width(&self, line: &str) -> usize {
+        self.depth.get() * self.indent_width + line.chars().count()
+    }
+
+    /* Joins a parenthesized list (`head` ends with the opening parenthesis, `tail` starts with the closing one).
+       A list that does not fit on the line gets one item per line, aligned after the parenthesis,
+       or indented on the lines after it when even that is too wide */
+    pub fn wrap_list(&self, head: &str, items: &[String], tail: &str) -> String {
+        let line = format!("{}{}{}", head, items.join(", "), tail);
+
+        if items.len() < 2 || self.line_width(&line) <= self.max_line_width {
+            return line;
+        }
+
+        let last = items.len() - 1;
+        let aligned_width = head.chars().count();
+        let fits_aligned = items.iter().enumerate().all(|(index, item)| {
+            let end = if index == last { tail.chars().count() } else { 1 };
+            self.line_width(&" ".repeat(aligned_width)) + item.chars().count() + end <= self.max_line_width
+        });
+
+        let (first_separator, padding) = if fits_aligned {
+            (String::new(), format!("{}{}", self.indent(), " ".repeat(aligned_width)))
+        } else {
+            let padding = format!("{}{}", self.indent(), " ".repeat(self.indent_width));
+            (format!("\n{}", padding), padding)
+        };
+
+        format!("{}{}{}{}", head, first_separator, items.join(format!(",\n{}", padding).as_str()), tail)
+    }
+
+    /* A .clang-format style that matches the layout of this printer. Only call statements and parameter lists are wrapped,
+       so clang-format may still reflow other lines longer than max_line_width (long initializers and expressions) */
+    pub fn clang_format_style(&self) -> String {
+        let braces = match self.brace_style {
+            CBraceStyle::Attach => "Attach",
+            CBraceStyle::KAndR => "Linux",
+            CBraceStyle::Allman => "Allman",
+            CBraceStyle::GNU => "GNU",
+        };
+
+        format!(
+            "BasedOnStyle: LLVM
+IndentWidth: {width}
+ContinuationIndentWidth: {width}
+TabWidth: {width}
+UseTab: {tabs}
+ColumnLimit: {columns}
+BreakBeforeBraces: {braces}
+IndentCaseLabels: true
+SortIncludes: false
+BreakStringLiterals: false
+Cpp11BracedListStyle: false
+AllowShortFunctionsOnASingleLine: None
+AllowShortEnumsOnASingleLine: false
+BinPackArguments: false
+BinPackParameters: false
+AllowAllArgumentsOnNextLine: false
+AllowAllParametersOfDeclarationOnNextLine: false
+",
+            width = self.indent_width,
+            tabs = if self.use_tabs { "ForIndentation" } else { "Never" },
+            columns = self.max_line_width,
+            braces = braces,
+        )
+    }
+}
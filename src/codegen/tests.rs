@@ -0,0 +1,1054 @@
+// ©2025 - BestJasmine - BestMat - All rights reserved.
+
+use super::*;
+use super::printer::CBraceStyle;
+
+fn number(value: u64) -> CExpression {
+    CExpression::NumberExpression(CNumberExpression::new(value))
+}
+
+fn identifier(name: &str) -> CExpression {
+    CExpression::IdentifierExpression(CIdentifierExpression::new(name))
+}
+
+fn variable(var_type: CType, var_name: &str, var_value: CExpression) -> CStatement {
+    CStatement::VariableStatement(CVariableStatement::new(var_type, var_name, var_value))
+}
+
+/* Renders a program whose only node is `int main()` with the given statements */
+fn render_main(statements: Vec<CStatement>) -> JasmineResult<String> {
+    render_main_in(Codegen::new().standard, statements)
+}
+
+fn render_main_in(standard: CStandard, statements: Vec<CStatement>) -> JasmineResult<String> {
+    let mut codegen = Codegen::new();
+    codegen.standard = standard;
+    codegen.add_function_statement(CFunctionStatement::new(CType::Int, "main", Vec::new(), CBlockStatement { block: statements }));
+
+    codegen.render()
+}
+
+#[test]
+fn initializers_accept_any_arithmetic_type() {
+    let code = render_main(vec![
+        variable(CType::Int32, "a", number(5)),
+        variable(CType::UInt8, "b", number(5)),
+        variable(CType::Long(Box::new(CType::Int)), "c", number(5)),
+        variable(CType::Float, "d", CExpression::DecimalExpression(CDecimalExpression::double(2.5))),
+        variable(CType::Const(Box::new(CType::Int)), "e", number(5)),
+    ]).unwrap();
+
+    assert!(code.contains("    int32_t a = 5;\n"));
+    assert!(code.contains("    uint8_t b = 5;\n"));
+    assert!(code.contains("    long int c = 5;\n"));
+    assert!(code.contains("    float d = 2.5;\n"));
+    assert!(code.contains("    const int e = 5;\n"));
+}
+
+#[test]
+fn initializers_reject_incompatible_types() {
+    let error = render_main(vec![
+        variable(CType::Int, "x", CExpression::StringExpression(CStringExpression::new("text"))),
+    ]).unwrap_err();
+
+    assert!(matches!(error, JasmineError::TypeMismatch { .. }));
+    assert_eq!(error.path().unwrap().nodes, vec!["function main", "statement 1 (variable x)"]);
+}
+
+#[test]
+fn pointer_initializers_ignore_qualifiers_the_target_adds() {
+    let int_pointer = CType::Pointer(Box::new(CType::Int));
+    let address_of_x = CExpression::UnaryExpression(CUnaryExpression::new(
+        CUnaryOperator::AddressOf,
+        CExpression::IdentifierExpression(CIdentifierExpression::typed("x", CType::Int)),
+    ));
+
+    let code = render_main(vec![
+        variable(CType::Int, "x", number(1)),
+        variable(CType::Const(Box::new(int_pointer.clone())), "p", address_of_x.clone()),
+        variable(CType::Pointer(Box::new(CType::Const(Box::new(CType::Char)))), "s", CExpression::StringExpression(CStringExpression::new("hi"))),
+        variable(CType::Pointer(Box::new(CType::Void)), "v", address_of_x),
+    ]).unwrap();
+
+    assert!(code.contains("    int *const p = &x;\n"));
+    assert!(code.contains("    const char *s = \"hi\";\n"));
+    assert!(code.contains("    void *v = &x;\n"));
+}
+
+#[test]
+fn pointer_initializers_reject_dropped_qualifiers() {
+    let const_char_pointer = CType::Pointer(Box::new(CType::Const(Box::new(CType::Char))));
+
+    let error = render_main(vec![
+        variable(CType::Pointer(Box::new(CType::Char)), "s", CExpression::IdentifierExpression(CIdentifierExpression::typed("name", const_char_pointer))),
+    ]).unwrap_err();
+
+    assert!(matches!(error, JasmineError::TypeMismatch { .. }));
+}
+
+#[test]
+fn size_operators_initialize_integers() {
+    let code = render_main_in(CStandard::C11, vec![
+        variable(CType::Int, "n", CExpression::UnaryExpression(CUnaryExpression::of_type(CUnaryOperator::Sizeof, CType::Int))),
+        variable(CType::SizeT, "a", CExpression::UnaryExpression(CUnaryExpression::of_type(CUnaryOperator::Alignof, CType::Double))),
+    ]).unwrap();
+
+    assert!(code.contains("    int n = sizeof(int);\n"));
+    assert!(code.contains("    size_t a = _Alignof(double);\n"));
+}
+
+#[test]
+fn directives_inside_functions_end_their_line() {
+    let code = render_main(vec![
+        CStatement::DefineStatement(CDefineStatement::object("X", "1")),
+        CStatement::ConditionalStatement(CConditionalStatement {
+            condition: CPreprocessorCondition::Ifdef("DEBUG".to_string()),
+            block: vec![CProgramNode::CStatement(CStatement::ReturnStatement(CReturnStatement { value: number(2) }))],
+            elif_blocks: Vec::new(),
+            else_block: None,
+        }),
+        CStatement::ReturnStatement(CReturnStatement { value: identifier("X") }),
+    ]).unwrap();
+
+    assert_eq!(code, "int main(void) {\n#define X 1\n#ifdef DEBUG\n    return 2;\n#endif\n    return X;\n}\n");
+}
+
+#[test]
+fn negating_a_negative_decimal_keeps_both_signs_apart() {
+    let negative = CExpression::DecimalExpression(CDecimalExpression::double(-3.0));
+    let code = render_main(vec![
+        variable(CType::Double, "d", CExpression::UnaryExpression(CUnaryExpression::new(CUnaryOperator::Negate, negative))),
+    ]).unwrap();
+
+    assert!(code.contains("    double d = -(-3.0);\n"));
+}
+
+#[test]
+fn forward_prototypes_declare_an_empty_parameter_list() {
+    let mut codegen = Codegen::new();
+    codegen.add_function_statement(CFunctionStatement::new(CType::Int, "main", Vec::new(), CBlockStatement {
+        block: vec![CStatement::FunctionCallStatement(CFunctionCallStatement::new("helper", Vec::new()))],
+    }));
+    codegen.add_function_statement(CFunctionStatement::new(CType::Void, "helper", Vec::new(), CBlockStatement { block: Vec::new() }));
+
+    let code = codegen.render().unwrap();
+
+    assert!(code.starts_with("void helper(void);\n\nint main(void) {\n    helper();\n"));
+}
+
+#[test]
+fn function_specifiers_follow_the_standard() {
+    let mut function = CFunctionStatement::new(CType::Void, "stop", Vec::new(), CBlockStatement { block: Vec::new() });
+    function.specifiers = vec![CFunctionSpecifier::Inline, CFunctionSpecifier::Noreturn];
+
+    let mut codegen = Codegen::new();
+    codegen.add_function_statement(function);
+
+    codegen.standard = CStandard::C89;
+    assert!(codegen.render().unwrap().starts_with("void stop(void) {"));
+
+    codegen.standard = CStandard::C99;
+    assert!(codegen.render().unwrap().starts_with("inline void stop(void) {"));
+
+    codegen.standard = CStandard::C11;
+    assert!(codegen.render().unwrap().starts_with("inline _Noreturn void stop(void) {"));
+}
+
+#[test]
+fn tags_cannot_be_redefined() {
+    let field = |name: &str| CStructField { r#type: CType::Int, name: name.to_string(), bit_width: None };
+
+    let mut codegen = Codegen::new();
+    codegen.add_struct_definition(CStructDefinition { name: "P".to_string(), fields: vec![field("x")] });
+    codegen.add_union_definition(CUnionDefinition { name: "P".to_string(), fields: vec![field("y")] });
+
+    let error = codegen.render().unwrap_err();
+
+    assert!(matches!(error, JasmineError::InvalidNode { .. }));
+    assert_eq!(error.path().unwrap().nodes, vec!["union P"]);
+}
+
+#[test]
+fn definitions_used_by_value_are_pulled_ahead_once() {
+    let mut codegen = Codegen::new();
+    codegen.add_struct_definition(CStructDefinition {
+        name: "Line".to_string(),
+        fields: vec![CStructField { r#type: CType::CustomStructType("Point".to_string()), name: "start".to_string(), bit_width: None }],
+    });
+    codegen.add_struct_definition(CStructDefinition {
+        name: "Point".to_string(),
+        fields: vec![CStructField { r#type: CType::Int, name: "x".to_string(), bit_width: None }],
+    });
+
+    let code = codegen.render().unwrap();
+
+    assert_eq!(code.matches("struct Point {").count(), 1);
+    assert!(code.find("struct Point {") < code.find("struct Line {"));
+}
+
+#[test]
+fn mixed_sign_conversions_pick_a_valid_unsigned_type() {
+    let signed_long_long = CType::Signed(Box::new(CType::Long(Box::new(CType::Long(Box::new(CType::Int))))));
+    let common = CType::usual_arithmetic_conversion(&signed_long_long, &CType::SizeT).unwrap();
+
+    assert_eq!(common.to_string(), "unsigned long long int");
+    assert!(common.validate().is_ok());
+    assert_eq!(CType::Int64.to_unsigned(), CType::UInt64);
+    assert_eq!(CType::PtrdiffT.to_unsigned(), CType::SizeT);
+}
+
+#[test]
+fn struct_expressions_resolve_typedef_names() {
+    let mut codegen = Codegen::new();
+    codegen.add_struct_definition(CStructDefinition {
+        name: "P".to_string(),
+        fields: vec![CStructField { r#type: CType::Int, name: "x".to_string(), bit_width: None }],
+    });
+    codegen.add_typedef_statement(CTypedefStatement { name: "P".to_string(), ctype: CType::CustomStructType("P".to_string()) });
+    codegen.add_variable_statement(CVariableStatement::new(
+        CType::CustomTypedefType("P".to_string()),
+        "origin",
+        CExpression::StructExpression(CStructExpression::new(CType::CustomTypedefType("P".to_string()), vec![("x", number(0))])),
+    ));
+
+    assert!(codegen.render().unwrap().ends_with("typedef struct P P;\nP origin = { .x = 0 };\n"));
+}
+
+#[test]
+fn typedef_cycles_are_reported() {
+    let mut codegen = Codegen::new();
+    codegen.add_typedef_statement(CTypedefStatement { name: "B".to_string(), ctype: CType::CustomTypedefType("A".to_string()) });
+    codegen.add_typedef_statement(CTypedefStatement { name: "A".to_string(), ctype: CType::CustomTypedefType("B".to_string()) });
+
+    let error = codegen.render().unwrap_err();
+
+    assert!(matches!(error, JasmineError::InvalidNode { .. }));
+    assert_eq!(error.path().unwrap().nodes, vec!["typedef B"]);
+}
+
+#[test]
+fn non_ascii_characters_need_a_wide_prefix() {
+    let plain = render_main(vec![variable(CType::Char, "c", CExpression::CharExpression(CCharExpression::new('é')))]).unwrap_err();
+    assert!(matches!(plain, JasmineError::InvalidNode { .. }));
+
+    let euro = render_main(vec![variable(CType::Char, "c", CExpression::CharExpression(CCharExpression::new('€')))]).unwrap_err();
+    assert!(matches!(euro, JasmineError::InvalidNode { .. }));
+
+    let wide = CExpression::CharExpression(CCharExpression::new('€').with_prefix(CStringPrefix::Utf32));
+    let code = render_main_in(CStandard::C11, vec![variable(CType::CustomTypedefType("char32_t".to_string()), "c", wide)]).unwrap();
+    assert!(code.contains("    char32_t c = U'\\u20AC';\n"));
+}
+
+#[test]
+fn strings_escape_text_and_initialize_matching_arrays() {
+    let wide_array = CType::Array(Box::new(CType::CustomTypedefType("wchar_t".to_string())), CArraySize::Unspecified);
+    let char_array = CType::Array(Box::new(CType::Char), CArraySize::Constant(8));
+
+    let code = render_main(vec![
+        variable(wide_array, "w", CExpression::StringExpression(CStringExpression::new("hi").with_prefix(CStringPrefix::Wide))),
+        variable(char_array.clone(), "s", CExpression::StringExpression(CStringExpression::new("é\t\"a"))),
+    ]).unwrap();
+
+    assert!(code.starts_with("#include <stddef.h>\n"));
+    assert!(code.contains("    wchar_t w[] = L\"hi\";\n"));
+    assert!(code.contains("    char s[8] = \"\\xC3\\xA9\\t\\\"a\";\n"));
+
+    let error = render_main(vec![
+        variable(char_array, "s", CExpression::StringExpression(CStringExpression::new("hi").with_prefix(CStringPrefix::Wide))),
+    ]).unwrap_err();
+    assert!(matches!(error, JasmineError::TypeMismatch { .. }));
+}
+
+#[test]
+fn cast_warnings_are_collected_by_render() {
+    let truncating = CExpression::CastExpression(CCastExpression::new(CType::Int, CExpression::DecimalExpression(CDecimalExpression::double(2.5))));
+
+    let mut codegen = Codegen::new();
+    codegen.check_casts = true;
+    codegen.add_function_statement(CFunctionStatement::new(CType::Int, "main", Vec::new(), CBlockStatement {
+        block: vec![variable(CType::Int, "n", truncating)],
+    }));
+
+    codegen.render().unwrap();
+    assert_eq!(codegen.warnings(), vec!["cast from `double` to `int` truncates the fractional part"]);
+
+    // Every render starts over:
+    codegen.check_casts = false;
+    codegen.render().unwrap();
+    assert!(codegen.warnings().is_empty());
+}
+
+#[test]
+fn casts_to_void_are_not_suspicious() {
+    let pointer = CExpression::IdentifierExpression(CIdentifierExpression::typed("p", CType::Pointer(Box::new(CType::Int))));
+
+    assert_eq!(CCastExpression::new(CType::Void, pointer.clone()).check(), None);
+    assert!(CCastExpression::new(CType::Float, pointer).check().is_some());
+}
+
+#[test]
+fn missing_headers_follow_the_includes_and_guards_of_the_program() {
+    let size = || CStatement::VariableStatement(CVariableStatement::new(CType::SizeT, "size", number(0)));
+
+    let mut codegen = Codegen::new();
+    codegen.add_pragma_statement(CPragma::Once);
+    codegen.add_variable_statement(CVariableStatement::new(CType::SizeT, "size", number(0)));
+    assert_eq!(codegen.render().unwrap(), "#pragma once\n#include <stddef.h>\nsize_t size = 0;\n");
+
+    let mut codegen = Codegen::new();
+    codegen.add_define_statement(CDefineStatement::object("MAX", "8"));
+    codegen.add_include_statement(CLibrary::stdio());
+    codegen.add_variable_statement(CVariableStatement::new(CType::SizeT, "size", number(0)));
+    assert_eq!(codegen.render().unwrap(), "#define MAX 8\n#include <stdio.h>\n#include <stddef.h>\nsize_t size = 0;\n");
+
+    let mut codegen = Codegen::new();
+    codegen.add_conditional_statement(CConditionalStatement {
+        condition: CPreprocessorCondition::Ifndef("MAIN_H".to_string()),
+        block: vec![
+            CProgramNode::CStatement(CStatement::DefineStatement(CDefineStatement::object("MAIN_H", ""))),
+            CProgramNode::CStatement(size()),
+        ],
+        elif_blocks: Vec::new(),
+        else_block: None,
+    });
+    assert_eq!(codegen.render().unwrap(), "#ifndef MAIN_H\n#define MAIN_H\n#include <stddef.h>\nsize_t size = 0;\n#endif\n");
+}
+
+#[test]
+fn booleans_follow_the_standard() {
+    let flag = || variable(CType::Bool, "flag", CExpression::BooleanExpression(CBooleanExpression::new(true)));
+
+    assert!(render_main(vec![flag()]).unwrap().starts_with("#include <stdbool.h>\n"));
+
+    let mut codegen = Codegen::new();
+    codegen.standard = CStandard::C23;
+    codegen.add_function_statement(CFunctionStatement::new(CType::Int, "main", Vec::new(), CBlockStatement { block: vec![flag()] }));
+    assert!(codegen.render().unwrap().starts_with("int main(void) {\n    bool flag = true;\n"));
+
+    codegen.standard = CStandard::C89;
+    assert!(matches!(codegen.render().unwrap_err(), JasmineError::InvalidNode { .. }));
+}
+
+#[test]
+fn member_accesses_take_the_type_of_their_field() {
+    let point = CType::CustomStructType("Point".to_string());
+    let member = |object: CExpression, name: &str| CExpression::MemberExpression(CMemberExpression::dot(object, name));
+    let arrow = |object: CExpression, name: &str| CExpression::MemberExpression(CMemberExpression::arrow(object, name));
+    let p = || CExpression::IdentifierExpression(CIdentifierExpression::typed("p", CType::Pointer(Box::new(CType::CustomStructType("Point".to_string())))));
+    let render = |statements: Vec<CStatement>| {
+        let mut codegen = Codegen::new();
+        codegen.add_struct_definition(CStructDefinition {
+            name: "Point".to_string(),
+            fields: vec![
+                CStructField { r#type: CType::Int, name: "x".to_string(), bit_width: None },
+                CStructField { r#type: CType::Pointer(Box::new(CType::Char)), name: "name".to_string(), bit_width: None },
+            ],
+        });
+        codegen.add_function_statement(CFunctionStatement::new(CType::Int, "main", Vec::new(), CBlockStatement { block: statements }));
+        codegen.render()
+    };
+
+    let code = render(vec![
+        variable(point.clone(), "origin", CExpression::StructExpression(CStructExpression::new(point.clone(), vec![("x", number(0))]))),
+        variable(CType::Long(Box::new(CType::Int)), "x", member(CExpression::IdentifierExpression(CIdentifierExpression::typed("origin", point)), "x")),
+        variable(CType::Pointer(Box::new(CType::Const(Box::new(CType::Char)))), "name", arrow(p(), "name")),
+    ]).unwrap();
+    assert!(code.contains("    long int x = origin.x;\n    const char *name = p->name;\n"));
+
+    let mismatch = render(vec![variable(CType::Int, "name", arrow(p(), "name"))]).unwrap_err();
+    assert!(matches!(mismatch, JasmineError::TypeMismatch { .. }));
+
+    let missing = render(vec![variable(CType::Int, "y", arrow(p(), "y"))]).unwrap_err();
+    assert!(matches!(missing, JasmineError::UndefinedIdentifier { .. }));
+
+    let wrong_operator = render(vec![variable(CType::Int, "x", member(p(), "x"))]).unwrap_err();
+    assert!(matches!(wrong_operator, JasmineError::TypeMismatch { .. }));
+}
+
+#[test]
+fn write_to_renders_into_any_writer() {
+    let mut codegen = Codegen::new();
+    codegen.add_include_statement(CLibrary::stdio());
+
+    let mut buffer = Vec::new();
+    codegen.write_to(&mut buffer).unwrap();
+
+    assert_eq!(String::from_utf8(buffer).unwrap(), "#include <stdio.h>\n");
+}
+
+#[test]
+fn emit_to_file_only_rewrites_changed_code() {
+    let directory = std::env::temp_dir().join(format!("bestjasmine-emit-{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    let path = directory.join("main.c");
+
+    let mut codegen = Codegen::new();
+    codegen.add_include_statement(CLibrary::stdio());
+
+    assert!(codegen.emit_to_file(&path).unwrap());
+    assert!(!codegen.emit_to_file(&path).unwrap());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "#include <stdio.h>\n");
+
+    // No temporary file is left behind:
+    assert_eq!(fs::read_dir(&directory).unwrap().count(), 1);
+
+    let error = codegen.emit_to_file(directory.join("..")).unwrap_err();
+    assert!(matches!(error, JasmineError::Io { .. }));
+
+    fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn null_pointer_constants_and_pointer_truth_values_initialize() {
+    let int_pointer = CType::Pointer(Box::new(CType::Int));
+
+    let code = render_main(vec![
+        variable(int_pointer.clone(), "p", number(0)),
+        variable(CType::Bool, "valid", CExpression::IdentifierExpression(CIdentifierExpression::typed("p", int_pointer.clone()))),
+    ]).unwrap();
+
+    assert!(code.contains("    int *p = 0;\n    bool valid = p;\n"));
+
+    let error = render_main(vec![variable(int_pointer, "p", number(1))]).unwrap_err();
+    assert!(matches!(error, JasmineError::TypeMismatch { .. }));
+}
+
+#[test]
+fn undefined_typedef_names_are_not_checked() {
+    let typedef = |name: &str| CType::CustomTypedefType(name.to_string());
+
+    let code = render_main_in(CStandard::C11, vec![
+        variable(typedef("wchar_t"), "w", number(0)),
+        variable(typedef("time_t"), "t", number(0)),
+        variable(typedef("char16_t"), "c", CExpression::CharExpression(CCharExpression::new('a'))),
+        variable(CType::Int, "n", CExpression::CharExpression(CCharExpression::new('a').with_prefix(CStringPrefix::Wide))),
+    ]).unwrap();
+
+    assert!(code.contains("    wchar_t w = 0;\n    time_t t = 0;\n    char16_t c = 'a';\n    int n = L'a';\n"));
+}
+
+#[test]
+fn declarations_inside_functions_are_indented_statements() {
+    let code = render_main(vec![
+        CStatement::TypedefStatement(CTypedefStatement { name: "T".to_string(), ctype: CType::Int }),
+        CStatement::StructDefinition(CStructDefinition {
+            name: "S".to_string(),
+            fields: vec![CStructField { r#type: CType::Int, name: "x".to_string(), bit_width: None }],
+        }),
+        CStatement::FunctionStatement(CFunctionStatement::prototype(CType::Void, "helper", Vec::new())),
+        variable(CType::Int, "b", number(2)),
+    ]).unwrap();
+
+    assert_eq!(code, "int main(void) {\n    typedef int T;\n    struct S {\n        int x;\n    };\n    void helper(void);\n    int b = 2;\n}\n");
+
+    let nested = CFunctionStatement::new(CType::Int, "inner", Vec::new(), CBlockStatement { block: Vec::new() });
+    let error = render_main(vec![CStatement::FunctionStatement(nested)]).unwrap_err();
+
+    assert!(matches!(error, JasmineError::UnsupportedNode { .. }));
+}
+
+/* `int main()` with an if/else whose branches return, rendered with the given brace style */
+fn render_braces(brace_style: CBraceStyle) -> String {
+    let mut codegen = Codegen::new();
+    codegen.printer.brace_style = brace_style;
+    codegen.add_function_statement(CFunctionStatement::new(CType::Int, "main", Vec::new(), CBlockStatement {
+        block: vec![CStatement::IfStatement(CIfStatement {
+            condition: identifier("x"),
+            then_block: CBlockStatement { block: vec![CStatement::ReturnStatement(CReturnStatement { value: number(1) })] },
+            else_if_blocks: Vec::new(),
+            else_block: Some(CBlockStatement { block: vec![CStatement::ReturnStatement(CReturnStatement { value: number(0) })] }),
+        })],
+    }));
+
+    codegen.render().unwrap()
+}
+
+#[test]
+fn brace_styles_place_the_braces() {
+    assert_eq!(render_braces(CBraceStyle::Attach), "int main(void) {\n    if (x) {\n        return 1;\n    } else {\n        return 0;\n    }\n}\n");
+    assert_eq!(render_braces(CBraceStyle::KAndR), "int main(void)\n{\n    if (x) {\n        return 1;\n    } else {\n        return 0;\n    }\n}\n");
+    assert_eq!(render_braces(CBraceStyle::Allman), "int main(void)\n{\n    if (x)\n    {\n        return 1;\n    }\n    else\n    {\n        return 0;\n    }\n}\n");
+    assert_eq!(render_braces(CBraceStyle::GNU), "int main(void)\n{\n    if (x)\n        {\n            return 1;\n        }\n    else\n        {\n            return 0;\n        }\n}\n");
+}
+
+#[test]
+fn long_calls_and_parameter_lists_are_wrapped() {
+    let argument = |name: &str| CFunctionArg { r#type: CType::Pointer(Box::new(CType::Const(Box::new(CType::Char)))), name: name.to_string() };
+    let call = CFunctionCallStatement::new("format_message", vec![identifier("first_argument"), identifier("second_argument"), identifier("third_argument"), identifier("fourth_argument")]);
+
+    let mut codegen = Codegen::new();
+    codegen.add_function_statement(CFunctionStatement::new(
+        CType::Void,
+        "format_message",
+        vec![argument("first_argument"), argument("second_argument"), argument("third_argument"), argument("fourth_argument")],
+        CBlockStatement { block: Vec::new() },
+    ));
+    codegen.add_function_statement(CFunctionStatement::new(CType::Int, "main", Vec::new(), CBlockStatement {
+        block: vec![CStatement::FunctionCallStatement(call)],
+    }));
+
+    let code = codegen.render().unwrap();
+
+    assert!(code.starts_with("void format_message(const char *first_argument,\n                    const char *second_argument,\n                    const char *third_argument,\n                    const char *fourth_argument) {\n}\n"));
+    assert!(code.contains("    format_message(first_argument,\n                   second_argument,\n                   third_argument,\n                   fourth_argument);\n"));
+    assert!(code.lines().all(|line| line.chars().count() <= codegen.printer.max_line_width));
+}
+
+#[test]
+fn clang_format_file_matches_the_default_printer() {
+    assert_eq!(include_str!("../../.clang-format"), CPrinter::new().clang_format_style());
+}
+
+#[test]
+fn binary_expressions_promote_their_operands() {
+    let typed = |name: &str, ctype: CType| CExpression::IdentifierExpression(CIdentifierExpression::typed(name, ctype));
+    let short = CType::Short(Box::new(CType::Int));
+
+    let sum = CBinaryExpression::new(typed("c", CType::Char), CBinaryOperator::Add, typed("c", CType::Char));
+    let shift = CBinaryExpression::new(typed("s", short.clone()), CBinaryOperator::ShiftLeft, number(1));
+    let wide_shift = CBinaryExpression::new(typed("s", short), CBinaryOperator::ShiftLeft, typed("n", CType::Long(Box::new(CType::Int))));
+
+    assert_eq!(sum.get_type(), Some(CType::Int));
+    assert_eq!(shift.get_type(), Some(CType::Int));
+    assert_eq!(wide_shift.get_type(), Some(CType::Int));
+
+    let truncation = CCastExpression::new(CType::Char, CExpression::BinaryExpression(sum));
+    assert!(truncation.check().is_some());
+}
+
+#[test]
+fn ternary_expressions_convert_equal_arithmetic_arms() {
+    let c = || CExpression::IdentifierExpression(CIdentifierExpression::typed("c", CType::Char));
+    let p = || CExpression::IdentifierExpression(CIdentifierExpression::typed("p", CType::Pointer(Box::new(CType::Char))));
+
+    assert_eq!(CTernaryExpression::new(identifier("x"), c(), c()).get_type(), Some(CType::Int));
+    assert_eq!(CTernaryExpression::new(identifier("x"), p(), p()).get_type(), Some(CType::Pointer(Box::new(CType::Char))));
+}
+
+#[test]
+fn constants_without_a_c_type_are_rejected() {
+    let float = |value: f64| variable(CType::Float, "f", CExpression::DecimalExpression(CDecimalExpression::new(value, CType::Float)));
+
+    assert!(render_main(vec![float(0.5)]).unwrap().contains("    float f = 0.5f;\n"));
+    assert!(matches!(render_main(vec![float(1e300)]).unwrap_err(), JasmineError::InvalidNode { .. }));
+    assert!(matches!(render_main(vec![float(1e-300)]).unwrap_err(), JasmineError::InvalidNode { .. }));
+
+    let huge = |number: CNumberExpression| variable(CType::UInt64, "n", CExpression::NumberExpression(number));
+    assert!(matches!(render_main(vec![huge(CNumberExpression::new(u64::MAX))]).unwrap_err(), JasmineError::InvalidNode { .. }));
+    assert!(render_main(vec![huge(CNumberExpression::new(u64::MAX).with_suffix(CIntegerSuffix::U))]).unwrap().contains(" = 18446744073709551615U;\n"));
+    assert!(render_main(vec![huge(CNumberExpression::hex(u64::MAX))]).unwrap().contains(" = 0xFFFFFFFFFFFFFFFF;\n"));
+}
+
+#[test]
+fn binary_constants_need_c23() {
+    let mut codegen = Codegen::new();
+    codegen.add_variable_statement(CVariableStatement::new(CType::Int, "mask", CExpression::NumberExpression(CNumberExpression::binary(5))));
+
+    assert!(matches!(codegen.render().unwrap_err(), JasmineError::InvalidNode { .. }));
+
+    codegen.standard = CStandard::C23;
+    assert_eq!(codegen.render().unwrap(), "int mask = 0b101;\n");
+}
+
+#[test]
+fn encoding_prefixes_follow_the_standard() {
+    let string = |prefix: CStringPrefix| variable(
+        CType::Pointer(Box::new(CType::Const(Box::new(prefix.element_type())))),
+        "s",
+        CExpression::StringExpression(CStringExpression::new("\u{85}é").with_prefix(prefix)),
+    );
+    let char = |prefix: CStringPrefix| variable(CType::Int, "c", CExpression::CharExpression(CCharExpression::new('a').with_prefix(prefix)));
+
+    assert!(render_main(vec![string(CStringPrefix::Wide)]).is_ok());
+    assert!(matches!(render_main(vec![string(CStringPrefix::Utf16)]).unwrap_err(), JasmineError::InvalidNode { .. }));
+    assert!(matches!(render_main(vec![string(CStringPrefix::Utf8)]).unwrap_err(), JasmineError::InvalidNode { .. }));
+    assert!(matches!(render_main(vec![char(CStringPrefix::Utf32)]).unwrap_err(), JasmineError::InvalidNode { .. }));
+
+    let code = render_main_in(CStandard::C11, vec![string(CStringPrefix::Utf8), string(CStringPrefix::Utf32), char(CStringPrefix::Utf16)]).unwrap();
+    assert!(code.contains("    const char *s = u8\"\\xC2\\x85\\xC3\\xA9\";\n"));
+    assert!(code.contains("    const char32_t *s = U\"\\x85\\u00E9\";\n"));
+    assert!(code.contains("    int c = u'a';\n"));
+
+    assert!(matches!(render_main_in(CStandard::C17, vec![char(CStringPrefix::Utf8)]).unwrap_err(), JasmineError::InvalidNode { .. }));
+    assert!(render_main_in(CStandard::C23, vec![char(CStringPrefix::Utf8)]).unwrap().contains("    int c = u8'a';\n"));
+
+    let error = render_main(vec![variable(CType::CustomTypedefType("char16_t".to_string()), "c", number(0))]).unwrap_err();
+    assert!(matches!(error, JasmineError::InvalidNode { .. }));
+}
+
+#[test]
+fn thread_local_variables_need_c11() {
+    let mut counter = CVariableStatement::new(CType::Int, "counter", number(0));
+    counter.thread_local = true;
+
+    let mut codegen = Codegen::new();
+    codegen.add_variable_statement(counter);
+
+    assert!(matches!(codegen.render().unwrap_err(), JasmineError::InvalidNode { .. }));
+
+    codegen.standard = CStandard::C11;
+    assert_eq!(codegen.render().unwrap(), "_Thread_local int counter = 0;\n");
+}
+
+#[test]
+fn unary_operators_check_their_operands() {
+    let unary = |operator: CUnaryOperator, operand: CExpression| CExpression::UnaryExpression(CUnaryExpression::new(operator, operand));
+    let invalid_as = |standard: CStandard, ctype: CType, value: CExpression| {
+        matches!(render_main_in(standard, vec![variable(ctype, "n", value)]).unwrap_err(), JasmineError::InvalidNode { .. })
+    };
+    let invalid = |standard: CStandard, value: CExpression| invalid_as(standard, CType::Int, value);
+
+    assert!(invalid(CStandard::C11, CExpression::UnaryExpression(CUnaryExpression::of_type(CUnaryOperator::Negate, CType::Int))));
+    assert!(invalid(CStandard::C11, unary(CUnaryOperator::Alignof, identifier("x"))));
+    assert!(invalid(CStandard::C99, CExpression::UnaryExpression(CUnaryExpression::of_type(CUnaryOperator::Alignof, CType::Int))));
+    assert!(invalid(CStandard::C11, unary(CUnaryOperator::PostIncrement, number(3))));
+    assert!(invalid_as(CStandard::C11, CType::Pointer(Box::new(CType::Int)), unary(CUnaryOperator::AddressOf, number(3))));
+
+    let code = render_main(vec![
+        variable(CType::Int, "x", number(1)),
+        variable(CType::Int, "y", unary(CUnaryOperator::PostIncrement, identifier("x"))),
+        variable(CType::SizeT, "size", unary(CUnaryOperator::Sizeof, identifier("x"))),
+    ]).unwrap();
+    assert!(code.contains("    int y = x++;\n    size_t size = sizeof(x);\n"));
+}
+
+#[test]
+fn casts_compare_widths_and_reject_aggregates() {
+    let typed = |name: &str, ctype: CType| CExpression::IdentifierExpression(CIdentifierExpression::typed(name, ctype));
+    let long_long = CType::Long(Box::new(CType::Long(Box::new(CType::Int))));
+    let pointer = CType::Pointer(Box::new(CType::Int));
+
+    assert_eq!(CCastExpression::new(CType::Int64, typed("n", long_long.clone())).check(), None);
+    assert!(CCastExpression::new(CType::Int32, typed("n", long_long)).check().is_some());
+    assert_eq!(CCastExpression::new(CType::UIntPtr, typed("p", pointer.clone())).check(), None);
+    assert_eq!(CCastExpression::new(pointer.clone(), typed("address", CType::IntPtr)).check(), None);
+    assert!(CCastExpression::new(CType::Long(Box::new(CType::Int)), typed("p", pointer)).check().is_some());
+
+    let to_struct = CCastExpression::new(CType::CustomStructType("P".to_string()), identifier("x"));
+    let error = render_main(vec![variable(CType::CustomStructType("P".to_string()), "p", CExpression::CastExpression(to_struct))]).unwrap_err();
+    assert!(matches!(error, JasmineError::TypeMismatch { .. }));
+}
+
+#[test]
+fn variable_length_arrays_are_automatic_variables() {
+    let count = || CExpression::IdentifierExpression(CIdentifierExpression::typed("count", CType::Int));
+    let vla = |size: CExpression| CType::Array(Box::new(CType::Int), CArraySize::Variable(Box::new(size)));
+    let declare = |ctype: CType| CStatement::VariableStatement(CVariableStatement::uninitialized(ctype, "values"));
+
+    let code = render_main(vec![declare(vla(CExpression::BinaryExpression(CBinaryExpression::new(count(), CBinaryOperator::Add, number(1)))))]).unwrap();
+    assert!(code.contains("    int values[count + 1];\n"));
+
+    let mut codegen = Codegen::new();
+    codegen.add_variable_statement(CVariableStatement::uninitialized(vla(count()), "values"));
+    assert!(matches!(codegen.render().unwrap_err(), JasmineError::InvalidNode { .. }));
+
+    let mut static_vla = CVariableStatement::uninitialized(vla(count()), "values");
+    static_vla.storage_class = Some(CStorageClass::Static);
+    assert!(matches!(render_main(vec![CStatement::VariableStatement(static_vla)]).unwrap_err(), JasmineError::InvalidNode { .. }));
+
+    let decimal = CExpression::DecimalExpression(CDecimalExpression::double(2.5));
+    assert!(matches!(render_main(vec![declare(vla(decimal))]).unwrap_err(), JasmineError::TypeMismatch { .. }));
+
+    let empty = CType::Array(Box::new(CType::Int), CArraySize::Constant(0));
+    assert!(matches!(render_main(vec![declare(empty)]).unwrap_err(), JasmineError::InvalidNode { .. }));
+}
+
+fn ifdef(name: &str, block: Vec<CStatement>, else_block: Vec<CStatement>) -> CStatement {
+    CStatement::ConditionalStatement(CConditionalStatement {
+        condition: CPreprocessorCondition::Ifdef(name.to_string()),
+        block: block.into_iter().map(CProgramNode::CStatement).collect(),
+        elif_blocks: Vec::new(),
+        else_block: Some(else_block.into_iter().map(CProgramNode::CStatement).collect()),
+    })
+}
+
+#[test]
+fn labels_are_checked_per_function() {
+    let label = |name: &str| CStatement::LabelStatement(name.to_string());
+    let goto = |name: &str| CStatement::GotoStatement(name.to_string());
+    let done = || CStatement::ReturnStatement(CReturnStatement { value: number(0) });
+
+    let code = render_main(vec![goto("cleanup"), ifdef("DEBUG", vec![label("cleanup"), done()], vec![done()])]).unwrap();
+    assert!(code.contains("    goto cleanup;\n#ifdef DEBUG\ncleanup:\n    return 0;\n#else\n    return 0;\n#endif\n"));
+
+    let duplicate = render_main(vec![label("retry"), done(), label("retry"), done()]).unwrap_err();
+    assert!(matches!(duplicate, JasmineError::InvalidNode { .. }));
+
+    let undefined = render_main(vec![goto("missing")]).unwrap_err();
+    assert!(matches!(undefined, JasmineError::UndefinedIdentifier { ref name, .. } if name == "missing"));
+}
+
+#[test]
+fn calls_inside_conditionals_get_prototypes() {
+    let mut codegen = Codegen::new();
+    codegen.add_function_statement(CFunctionStatement::new(CType::Int, "main", Vec::new(), CBlockStatement {
+        block: vec![ifdef("DEBUG", vec![CStatement::FunctionCallStatement(CFunctionCallStatement::new("trace", Vec::new()))], Vec::new())],
+    }));
+    codegen.add_function_statement(CFunctionStatement::new(CType::Void, "trace", Vec::new(), CBlockStatement { block: Vec::new() }));
+
+    assert!(codegen.render().unwrap().starts_with("void trace(void);\n\nint main(void) {\n"));
+}
+
+fn switch(cases: Vec<CSwitchCase>, default_block: Option<CBlockStatement>) -> CStatement {
+    CStatement::SwitchStatement(CSwitchStatement { condition: identifier("x"), cases, default_block })
+}
+
+fn case(value: CExpression, block: Vec<CStatement>, fallthrough: bool) -> CSwitchCase {
+    CSwitchCase { value, block: CBlockStatement { block }, fallthrough }
+}
+
+#[test]
+fn switch_arms_break_unless_they_fall_through() {
+    let call = |name: &str| CStatement::FunctionCallStatement(CFunctionCallStatement::new(name, Vec::new()));
+
+    let code = render_main(vec![switch(
+        vec![
+            case(number(1), vec![call("one")], false),
+            case(number(2), Vec::new(), true),
+            case(number(3), vec![call("two_or_three")], true),
+        ],
+        Some(CBlockStatement { block: vec![CStatement::ReturnStatement(CReturnStatement { value: number(0) })] }),
+    )]).unwrap();
+
+    assert!(code.contains(concat!(
+        "    switch (x) {\n",
+        "        case 1:\n            one();\n            break;\n",
+        "        case 2:\n",
+        "        case 3:\n            two_or_three();\n",
+        "        default:\n            return 0;\n",
+        "    }\n",
+    )));
+
+    let code = render_main(vec![switch(vec![case(number(1), Vec::new(), true)], None)]).unwrap();
+    assert!(code.contains("        case 1:\n            ;\n    }\n"));
+}
+
+#[test]
+fn duplicate_case_values_are_rejected() {
+    let char = CExpression::CharExpression(CCharExpression::new('a'));
+    let duplicate = render_main(vec![switch(vec![case(char, Vec::new(), false), case(number(97), Vec::new(), false)], None)]).unwrap_err();
+    assert!(matches!(duplicate, JasmineError::InvalidNode { .. }));
+    assert_eq!(duplicate.path().unwrap().nodes, vec!["function main", "statement 1 (switch statement)", "case 2"]);
+
+    let mut codegen = Codegen::new();
+    codegen.add_enum_definition(CEnumDefinition {
+        name: "Color".to_string(),
+        enumerators: vec![
+            CEnumerator { name: "RED".to_string(), value: Some(1) },
+            CEnumerator { name: "GREEN".to_string(), value: None },
+        ],
+    });
+    codegen.add_function_statement(CFunctionStatement::new(CType::Int, "main", Vec::new(), CBlockStatement {
+        block: vec![switch(vec![case(identifier("GREEN"), Vec::new(), false), case(number(2), Vec::new(), false)], None)],
+    }));
+    assert!(matches!(codegen.render().unwrap_err(), JasmineError::InvalidNode { .. }));
+}
+
+#[test]
+fn if_statements_chain_else_if_and_else_blocks() {
+    let compare = |value: u64| CExpression::BinaryExpression(CBinaryExpression::new(identifier("x"), CBinaryOperator::Equal, number(value)));
+    let returns = |value: u64| CBlockStatement { block: vec![CStatement::ReturnStatement(CReturnStatement { value: number(value) })] };
+
+    let code = render_main(vec![CStatement::IfStatement(CIfStatement {
+        condition: compare(27),
+        then_block: returns(1),
+        else_if_blocks: vec![
+            CElseIfStatement { condition: compare(21), block: returns(2) },
+            CElseIfStatement { condition: compare(7), block: CBlockStatement { block: Vec::new() } },
+        ],
+        else_block: Some(returns(3)),
+    })]).unwrap();
+
+    assert_eq!(code, concat!(
+        "int main(void) {\n",
+        "    if (x == 27) {\n        return 1;\n",
+        "    } else if (x == 21) {\n        return 2;\n",
+        "    } else if (x == 7) {\n",
+        "    } else {\n        return 3;\n",
+        "    }\n",
+        "}\n",
+    ));
+
+    let error = render_main(vec![CStatement::IfStatement(CIfStatement {
+        condition: compare(1),
+        then_block: CBlockStatement { block: Vec::new() },
+        else_if_blocks: vec![CElseIfStatement { condition: identifier("y"), block: CBlockStatement { block: vec![CStatement::BreakStatement] } }],
+        else_block: None,
+    })]).unwrap_err();
+    assert_eq!(error.path().unwrap().nodes, vec!["function main", "statement 1 (if statement)", "else if block 1", "statement 1 (break statement)"]);
+}
+
+fn handler_type() -> CType {
+    CType::FunctionPointer { ret: Box::new(CType::Int), params: vec![CType::Int, CType::Pointer(Box::new(CType::Char))], variadic: false }
+}
+
+#[test]
+fn function_pointer_declarators_wrap_the_name() {
+    let mut codegen = Codegen::new();
+    codegen.add_variable_statement(CVariableStatement::uninitialized(handler_type(), "f"));
+    codegen.add_variable_statement(CVariableStatement::uninitialized(CType::Array(Box::new(handler_type()), CArraySize::Constant(4)), "handlers"));
+    codegen.add_variable_statement(CVariableStatement::uninitialized(
+        CType::FunctionPointer { ret: Box::new(CType::Int), params: vec![CType::Pointer(Box::new(CType::Const(Box::new(CType::Char))))], variadic: true },
+        "log",
+    ));
+    codegen.add_function_statement(CFunctionStatement::prototype(
+        handler_type(),
+        "get_handler",
+        vec![CFunctionArg { r#type: CType::Int, name: "signal".to_string() }],
+    ));
+
+    assert_eq!(codegen.render().unwrap(), concat!(
+        "int (*f)(int, char *);\n",
+        "int (*handlers[4])(int, char *);\n",
+        "int (*log)(const char *, ...);\n",
+        "int (*get_handler(int signal))(int, char *);\n",
+    ));
+}
+
+#[test]
+fn calls_go_through_any_callee_expression() {
+    let call = |function: CExpression| CExpression::FunctionCallExpression(CFunctionCallStatement { function: Box::new(function), args: vec![identifier("x")] });
+    let fp = CExpression::IdentifierExpression(CIdentifierExpression::typed("fp", handler_type()));
+    let dereference = CExpression::UnaryExpression(CUnaryExpression::new(CUnaryOperator::Dereference, fp.clone()));
+    let callback = CExpression::MemberExpression(CMemberExpression::arrow(identifier("obj"), "cb"));
+    let indexed = CExpression::IndexExpression(CIndexExpression::new(identifier("handlers"), number(0)));
+
+    let code = render_main(vec![
+        variable(CType::Int, "a", call(dereference)),
+        variable(CType::Int, "b", call(callback)),
+        variable(CType::Int, "c", call(indexed)),
+        variable(CType::Int, "d", call(fp)),
+    ]).unwrap();
+
+    assert!(code.contains("    int a = (*fp)(x);\n    int b = obj->cb(x);\n    int c = handlers[0](x);\n    int d = fp(x);\n"));
+
+    let mismatch = render_main(vec![
+        variable(CType::Pointer(Box::new(CType::Char)), "s", call(CExpression::IdentifierExpression(CIdentifierExpression::typed("fp", handler_type())))),
+    ]).unwrap_err();
+    assert!(matches!(mismatch, JasmineError::TypeMismatch { .. }));
+}
+
+fn increment(name: &str) -> CExpression {
+    CExpression::UnaryExpression(CUnaryExpression::new(CUnaryOperator::PostIncrement, identifier(name)))
+}
+
+fn less_than(name: &str, value: u64) -> CExpression {
+    CExpression::BinaryExpression(CBinaryExpression::new(identifier(name), CBinaryOperator::LessThan, number(value)))
+}
+
+#[test]
+fn loops_wrap_their_bodies() {
+    let code = render_main(vec![
+        CStatement::WhileStatement(CWhileStatement {
+            condition: less_than("x", 27),
+            block: CBlockStatement { block: vec![CStatement::ContinueStatement] },
+        }),
+        CStatement::DoWhileStatement(CDoWhileStatement {
+            block: CBlockStatement { block: vec![CStatement::BreakStatement] },
+            condition: less_than("x", 21),
+        }),
+        CStatement::ForStatement(CForStatement {
+            init: Some(CForInit::VariableStatement(CVariableStatement::new(CType::Int, "i", number(0)))),
+            condition: Some(less_than("i", 27)),
+            step: Some(increment("i")),
+            block: CBlockStatement { block: Vec::new() },
+        }),
+        CStatement::ForStatement(CForStatement { init: None, condition: None, step: None, block: CBlockStatement { block: vec![CStatement::BreakStatement] } }),
+    ]).unwrap();
+
+    assert!(code.contains(concat!(
+        "    while (x < 27) {\n",
+        "        continue;\n",
+        "    }\n",
+        "    do {\n",
+        "        break;\n",
+        "    } while (x < 21);\n",
+        "    for (int i = 0; i < 27; i++) {\n",
+        "    }\n",
+        "    for (;;) {\n",
+        "        break;\n",
+        "    }\n",
+    )));
+}
+
+#[test]
+fn break_and_continue_need_an_enclosing_loop() {
+    let outside = render_main(vec![CStatement::BreakStatement]).unwrap_err();
+    assert!(matches!(outside, JasmineError::InvalidNode { .. }));
+    assert_eq!(outside.path().unwrap().nodes, vec!["function main", "statement 1 (break statement)"]);
+
+    // A switch allows `break` but not `continue`:
+    let in_switch = render_main(vec![switch(vec![case(number(1), vec![CStatement::ContinueStatement], false)], None)]).unwrap_err();
+    assert!(matches!(in_switch, JasmineError::InvalidNode { .. }));
+
+    // A failed loop does not leave its body's permission behind:
+    let mut codegen = Codegen::new();
+    let _ = codegen.generate_while_statement(&CWhileStatement {
+        condition: number(1),
+        block: CBlockStatement { block: vec![variable(CType::Int, "x", CExpression::StringExpression(CStringExpression::new("text")))] },
+    });
+    codegen.add_function_statement(CFunctionStatement::new(CType::Int, "main", Vec::new(), CBlockStatement { block: vec![CStatement::ContinueStatement] }));
+    assert!(matches!(codegen.render().unwrap_err(), JasmineError::InvalidNode { .. }));
+}
+
+fn assignment(target: CExpression, operator: CAssignmentOperator, value: CExpression) -> CStatement {
+    CStatement::AssignmentStatement(CAssignmentStatement { target, operator, value })
+}
+
+#[test]
+fn assignments_take_any_lvalue_and_compound_operator() {
+    let dereference = CExpression::UnaryExpression(CUnaryExpression::new(CUnaryOperator::Dereference, identifier("p")));
+    let arrow = CExpression::MemberExpression(CMemberExpression::arrow(identifier("p"), "x"));
+    let dot = CExpression::MemberExpression(CMemberExpression::dot(identifier("point"), "y"));
+    let index = CExpression::IndexExpression(CIndexExpression::new(identifier("values"), identifier("i")));
+
+    let code = render_main(vec![
+        assignment(identifier("x"), CAssignmentOperator::Assign, number(27)),
+        assignment(dereference, CAssignmentOperator::AddAssign, number(1)),
+        assignment(arrow, CAssignmentOperator::ShiftLeftAssign, number(2)),
+        assignment(dot, CAssignmentOperator::BitwiseXorAssign, number(3)),
+        assignment(index, CAssignmentOperator::ModuloAssign, number(4)),
+    ]).unwrap();
+
+    assert!(code.contains(concat!(
+        "    x = 27;\n",
+        "    *p += 1;\n",
+        "    p->x <<= 2;\n",
+        "    point.y ^= 3;\n",
+        "    values[i] %= 4;\n",
+    )));
+}
+
+#[test]
+fn assignments_reject_targets_that_are_not_lvalues() {
+    let sum = CExpression::BinaryExpression(CBinaryExpression::new(identifier("x"), CBinaryOperator::Add, number(1)));
+    let call = CExpression::FunctionCallExpression(CFunctionCallStatement::new("next", Vec::new()));
+
+    for target in [number(1), sum, increment("x"), call] {
+        let error = render_main(vec![assignment(target, CAssignmentOperator::Assign, number(0))]).unwrap_err();
+
+        assert!(matches!(error, JasmineError::InvalidNode { .. }));
+        assert_eq!(error.path().unwrap().nodes, vec!["function main", "statement 1 (assignment)"]);
+    }
+}
+
+fn render_point(value: CExpression) -> JasmineResult<String> {
+    let mut codegen = Codegen::new();
+    codegen.add_struct_definition(CStructDefinition {
+        name: "Point".to_string(),
+        fields: vec![
+            CStructField { r#type: CType::Int, name: "x".to_string(), bit_width: None },
+            CStructField { r#type: CType::Int, name: "y".to_string(), bit_width: None },
+        ],
+    });
+    codegen.add_function_statement(CFunctionStatement::new(CType::Int, "main", Vec::new(), CBlockStatement {
+        block: vec![variable(CType::CustomStructType("Point".to_string()), "p", value)],
+    }));
+
+    codegen.render()
+}
+
+fn point(fields: Vec<(&str, CExpression)>) -> CExpression {
+    CExpression::StructExpression(CStructExpression::new(CType::CustomStructType("Point".to_string()), fields))
+}
+
+#[test]
+fn struct_expressions_designate_their_fields() {
+    assert!(render_point(point(vec![("y", number(2)), ("x", number(1))])).unwrap().contains("    struct Point p = { .y = 2, .x = 1 };\n"));
+    assert!(render_point(point(Vec::new())).unwrap().contains("    struct Point p = { 0 };\n"));
+
+    // Outside of an initializer the expression is a compound literal:
+    let code = render_point(CExpression::TernaryExpression(CTernaryExpression::new(
+        identifier("flag"),
+        point(vec![("x", number(1))]),
+        point(vec![("y", number(1))]),
+    ))).unwrap();
+    assert!(code.contains("    struct Point p = flag ? (struct Point){ .x = 1 } : (struct Point){ .y = 1 };\n"));
+}
+
+#[test]
+fn struct_expressions_are_checked_against_their_definition() {
+    let unknown = render_point(point(vec![("x", number(1)), ("z", number(3))])).unwrap_err();
+    assert!(matches!(unknown, JasmineError::UndefinedIdentifier { .. }));
+    assert_eq!(unknown.path().unwrap().nodes, vec!["function main", "statement 1 (variable p)"]);
+
+    let duplicate = render_point(point(vec![("x", number(1)), ("x", number(2))])).unwrap_err();
+    assert!(matches!(duplicate, JasmineError::InvalidNode { .. }));
+
+    let not_a_struct = render_main(vec![
+        variable(CType::Int, "n", CExpression::StructExpression(CStructExpression::new(CType::Int, vec![("x", number(1))]))),
+    ]).unwrap_err();
+    assert!(matches!(not_a_struct, JasmineError::TypeMismatch { .. }));
+
+    // Without a definition the fields cannot be checked:
+    let code = render_main(vec![
+        variable(CType::CustomStructType("Extern".to_string()), "e", CExpression::StructExpression(CStructExpression::new(
+            CType::CustomStructType("Extern".to_string()),
+            vec![("anything", number(1))],
+        ))),
+    ]).unwrap();
+    assert!(code.contains("    struct Extern e = { .anything = 1 };\n"));
+}
+
+fn with_storage(mut statement: CVariableStatement, storage_class: CStorageClass) -> CVariableStatement {
+    statement.storage_class = Some(storage_class);
+    statement
+}
+
+#[test]
+fn variable_statements_declare_storage_classes_and_several_declarators() {
+    let int_pointer = CType::Pointer(Box::new(CType::Int));
+
+    let mut codegen = Codegen::new();
+    codegen.add_variable_statement(with_storage(CVariableStatement::new(CType::Int, "counter", number(0)), CStorageClass::Static));
+    codegen.add_variable_statement(with_storage(CVariableStatement::uninitialized(CType::Int, "shared"), CStorageClass::Extern));
+    codegen.add_variable_statement(with_storage(
+        CVariableStatement::uninitialized(CType::Array(Box::new(CType::Int), CArraySize::Unspecified), "table"),
+        CStorageClass::Extern,
+    ));
+    codegen.add_variable_statement(CVariableStatement::new(CType::Int, "x", number(1)).with_declarator("y", None).with_declarator("z", Some(number(3))));
+    codegen.add_variable_statement(CVariableStatement::uninitialized(int_pointer, "p").with_declarator("q", Some(number(0))));
+
+    assert_eq!(codegen.render().unwrap(), concat!(
+        "static int counter = 0;\n",
+        "extern int shared;\n",
+        "extern int table[];\n",
+        "int x = 1, y, z = 3;\n",
+        "int *p, *q = 0;\n",
+    ));
+
+    let code = render_main(vec![
+        CStatement::VariableStatement(with_storage(CVariableStatement::new(CType::Int, "calls", number(0)), CStorageClass::Static)),
+        CStatement::VariableStatement(with_storage(CVariableStatement::uninitialized(CType::Int, "shared"), CStorageClass::Extern)),
+        CStatement::VariableStatement(CVariableStatement::uninitialized(CType::Char, "c")),
+    ]).unwrap();
+    assert!(code.contains("    static int calls = 0;\n    extern int shared;\n    char c;\n"));
+}
+
+#[test]
+fn variable_statements_follow_the_declaration_rules() {
+    let extern_initialized = render_main(vec![
+        CStatement::VariableStatement(with_storage(CVariableStatement::uninitialized(CType::Int, "a").with_declarator("b", Some(number(1))), CStorageClass::Extern)),
+    ]).unwrap_err();
+    assert!(matches!(extern_initialized, JasmineError::InvalidNode { .. }));
+
+    let mut thread_local = CVariableStatement::new(CType::Int, "counter", number(0));
+    thread_local.thread_local = true;
+    let automatic_thread_local = render_main_in(CStandard::C11, vec![CStatement::VariableStatement(thread_local.clone())]).unwrap_err();
+    assert!(matches!(automatic_thread_local, JasmineError::InvalidNode { .. }));
+
+    let code = render_main_in(CStandard::C11, vec![CStatement::VariableStatement(with_storage(thread_local, CStorageClass::Static))]).unwrap();
+    assert!(code.contains("    static _Thread_local int counter = 0;\n"));
+
+    let unsized_array = render_main(vec![
+        CStatement::VariableStatement(CVariableStatement::uninitialized(CType::Array(Box::new(CType::Int), CArraySize::Unspecified), "values")),
+    ]).unwrap_err();
+    assert!(matches!(unsized_array, JasmineError::InvalidNode { .. }));
+
+    let mismatch = render_main(vec![
+        CStatement::VariableStatement(CVariableStatement::new(CType::Int, "x", number(1))
+            .with_declarator("y", Some(CExpression::StringExpression(CStringExpression::new("text"))))),
+    ]).unwrap_err();
+    assert!(matches!(mismatch, JasmineError::TypeMismatch { .. }));
+    assert_eq!(mismatch.path().unwrap().nodes, vec!["function main", "statement 1 (variable x)", "declarator y"]);
+}
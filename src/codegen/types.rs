@@ -1,6 +1,7 @@
 // ©2025 - BestJasmine - BestMat - All rights reserved.
 
 use std::fmt::{Display, Formatter};
+use crate::codegen::{CExpression, Codegen};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum CType {
@@ -14,9 +15,26 @@ pub enum CType {
     _Complex,
     _Imaginary,
 
+    /* Fixed-width C Types (<stdint.h>) */
+    Int8,                               // int8_t
+    Int16,                              // int16_t
+    Int32,                              // int32_t
+    Int64,                              // int64_t
+    UInt8,                              // uint8_t
+    UInt16,                             // uint16_t
+    UInt32,                             // uint32_t
+    UInt64,                             // uint64_t
+    IntPtr,                             // intptr_t
+    UIntPtr,                            // uintptr_t
+
+    /* Platform C Types */
+    SizeT,                              // size_t (<stddef.h>)
+    PtrdiffT,                           // ptrdiff_t (<stddef.h>)
+    Bool,                               // bool (<stdbool.h> before C23, where it became a keyword)
+
     /* Derived C Types */
     Pointer(Box<CType>),
-    Array(Box<CType>),
+    Array(Box<CType>, CArraySize),      // int[3], int[n], int[]
 
     /* Qualifier C Types */
     Const(Box<CType>),
@@ -27,6 +45,13 @@ pub enum CType {
     Unsigned(Box<CType>),
     Signed(Box<CType>),
 
+    /* Function C Types */
+    FunctionPointer {
+        ret: Box<CType>,                // Return type
+        params: Vec<CType>,             // Parameter types
+        variadic: bool,                 // int (*)(const char *, ...)
+    },
+
     /* Custom C Types */
     CustomTypedefType(String),
     CustomStructType(String),
@@ -34,30 +59,436 @@ pub enum CType {
     CustomUnionType(String),
 }
 
-impl Display for CType {
+#[derive(Debug, Clone, PartialEq)]
+pub enum CArraySize {
+    Unspecified,                        // int a[] = { 1, 2 };
+    Constant(usize),                    // int a[3];
+    Variable(Box<CExpression>),         // int a[n]; (variable-length array)
+}
+
+impl Display for CArraySize {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            CType::Int => write!(f, "int"),
-            CType::Char => write!(f, "char"),
-            CType::Float => write!(f, "float"),
-            CType::Double => write!(f, "double"),
-            CType::Void => write!(f, "void"),
-            CType::_Bool => write!(f, "_Bool"),
-            CType::_Complex => write!(f, "_Complex"),
-            CType::_Imaginary => write!(f, "_Imaginary"),
-            CType::Pointer(ctype) => write!(f, "{}*", *ctype),
-            CType::Array(ctype) => write!(f, "{}[]", *ctype),
-            CType::Const(ctype) => write!(f, "const {}", *ctype),
-            CType::Volatile(ctype) => write!(f, "volatile {}", *ctype),
-            CType::Restrict(ctype) => write!(f, "restrict {}", *ctype),
-            CType::Short(ctype) => write!(f, "short {}", *ctype),
-            CType::Long(ctype) => write!(f, "long {}", *ctype),
-            CType::Unsigned(ctype) => write!(f, "unsigned {}", *ctype),
-            CType::Signed(ctype) => write!(f, "signed {}", *ctype),
-            CType::CustomTypedefType(ctype) => write!(f, "{}", ctype),
-            CType::CustomStructType(ctype) => write!(f, "{}", ctype),
-            CType::CustomEnumType(ctype) => write!(f, "{}", ctype),
-            CType::CustomUnionType(ctype) => write!(f, "{}", ctype),
-        }
-    }
-}
\ No newline at end of file
+            CArraySize::Unspecified => Ok(()),
+            CArraySize::Constant(size) => write!(f, "{}", size),
+            // A type is written without a Codegen (in error messages too), so the size is generated with the default one.
+            // Codegen checks the size with its own settings before it writes a declaration:
+            CArraySize::Variable(size) => write!(f, "{}", Codegen::new().generate_expression(size).unwrap_or_default()),
+        }
+    }
+}
+
+impl CType {
+    /* Strips the outermost `const`/`volatile`/`restrict` qualifiers */
+    pub fn unqualified(&self) -> &CType {
+        match self {
+            CType::Const(ctype) | CType::Volatile(ctype) | CType::Restrict(ctype) => ctype.unqualified(),
+            _ => self,
+        }
+    }
+
+    /* The type a pointer or array points to (T for T* and T[]) */
+    pub fn pointee(&self) -> Option<&CType> {
+        match self.unqualified() {
+            CType::Pointer(ctype) | CType::Array(ctype, _) => Some(ctype),
+            _ => None,
+        }
+    }
+
+    pub fn is_pointer(&self) -> bool {
+        matches!(self.unqualified(), CType::Pointer(_))
+    }
+
+    pub fn is_const(&self) -> bool {
+        match self {
+            CType::Const(_) => true,
+            CType::Volatile(ctype) | CType::Restrict(ctype) => ctype.is_const(),
+            _ => false,
+        }
+    }
+
+    /* Integer conversion rank (_Bool < char < short < int < long < long long), None for non-integer types */
+    pub fn integer_rank(&self) -> Option<u8> {
+        match self.unqualified() {
+            CType::_Bool | CType::Bool => Some(1),
+            CType::Char | CType::Int8 | CType::UInt8 => Some(2),
+            CType::Short(_) | CType::Int16 | CType::UInt16 => Some(3),
+            CType::Int | CType::Int32 | CType::UInt32 | CType::CustomEnumType(_) => Some(4),
+            CType::Int64 | CType::UInt64 | CType::IntPtr | CType::UIntPtr | CType::SizeT | CType::PtrdiffT => Some(5),
+            CType::Long(ctype) => match ctype.unqualified() {
+                CType::Long(_) => Some(6),
+                ctype if ctype.integer_rank().is_some() => Some(5),
+                _ => None,
+            },
+            CType::Signed(ctype) | CType::Unsigned(ctype) => ctype.integer_rank(),
+            _ => None,
+        }
+    }
+
+    /* Width in bits of an integer type, None for non-integer types (assumes LP64, like usual_arithmetic_conversion) */
+    pub fn integer_width(&self) -> Option<u8> {
+        match self.integer_rank()? {
+            1 => Some(1),
+            2 => Some(8),
+            3 => Some(16),
+            4 => Some(32),
+            _ => Some(64),
+        }
+    }
+
+    /* Floating-point rank (float < double < long double), None for non-floating types */
+    pub fn floating_rank(&self) -> Option<u8> {
+        match self.unqualified() {
+            CType::Float => Some(1),
+            CType::Double => Some(2),
+            CType::Long(ctype) if *ctype.unqualified() == CType::Double => Some(3),
+            _ => None,
+        }
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.integer_rank().is_some()
+    }
+
+    pub fn is_floating(&self) -> bool {
+        self.floating_rank().is_some()
+    }
+
+    pub fn is_arithmetic(&self) -> bool {
+        self.is_integer() || self.is_floating()
+    }
+
+    pub fn is_unsigned(&self) -> bool {
+        match self.unqualified() {
+            CType::_Bool | CType::Bool | CType::Unsigned(_) => true,
+            CType::UInt8 | CType::UInt16 | CType::UInt32 | CType::UInt64 | CType::UIntPtr | CType::SizeT => true,
+            CType::Short(ctype) | CType::Long(ctype) => ctype.is_unsigned(),
+            _ => false,
+        }
+    }
+
+    /* The struct/union/enum tag that must be fully defined before this type can be used by value */
+    pub fn complete_type_dependency(&self) -> Option<&str> {
+        match self.unqualified() {
+            CType::CustomStructType(name) | CType::CustomUnionType(name) | CType::CustomEnumType(name) => Some(name),
+            CType::Array(ctype, _) => ctype.complete_type_dependency(),
+            _ => None,
+        }
+    }
+
+    /* Checks that `short`/`long`/`signed`/`unsigned` are only combined the ways C allows (eg: not `unsigned float`) */
+    pub fn validate(&self) -> Result<(), String> {
+        let is_plain_int = |ctype: &CType| matches!(ctype, CType::Int);
+        let valid = match self {
+            CType::Short(ctype) => is_plain_int(ctype),
+            CType::Long(ctype) => match &**ctype {
+                CType::Long(ctype) => is_plain_int(ctype),
+                ctype => is_plain_int(ctype) || *ctype == CType::Double,
+            },
+            CType::Signed(ctype) | CType::Unsigned(ctype) => match &**ctype {
+                CType::Short(_) | CType::Long(_) => ctype.validate().is_ok() && ctype.floating_rank().is_none(),
+                ctype => matches!(ctype, CType::Int | CType::Char),
+            },
+            _ => true,
+        };
+
+        if !valid {
+            return Err(format!("The type specifiers of {} cannot be combined", self));
+        }
+
+        match self {
+            CType::Pointer(ctype) | CType::Array(ctype, _) => ctype.validate(),
+            CType::Const(ctype) | CType::Volatile(ctype) | CType::Restrict(ctype) => ctype.validate(),
+            CType::FunctionPointer { ret, params, .. } => {
+                ret.validate()?;
+                params.iter().try_for_each(|param| param.validate())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /* Adds the standard headers that declare the types used by this type */
+    pub fn required_headers(&self, headers: &mut Vec<&'static str>) {
+        let header = match self {
+            CType::Int8 | CType::Int16 | CType::Int32 | CType::Int64 => Some("stdint.h"),
+            CType::UInt8 | CType::UInt16 | CType::UInt32 | CType::UInt64 => Some("stdint.h"),
+            CType::IntPtr | CType::UIntPtr => Some("stdint.h"),
+            CType::SizeT | CType::PtrdiffT => Some("stddef.h"),
+            CType::Bool => Some("stdbool.h"),
+            CType::CustomTypedefType(name) if name == "wchar_t" => Some("stddef.h"),
+            CType::CustomTypedefType(name) if name == "char16_t" || name == "char32_t" => Some("uchar.h"),
+            _ => None,
+        };
+
+        if let Some(header) = header {
+            if !headers.contains(&header) {
+                headers.push(header);
+            }
+        }
+
+        match self {
+            CType::Pointer(ctype) | CType::Array(ctype, _) => ctype.required_headers(headers),
+            CType::Const(ctype) | CType::Volatile(ctype) | CType::Restrict(ctype) => ctype.required_headers(headers),
+            CType::FunctionPointer { ret, params, .. } => {
+                ret.required_headers(headers);
+
+                for param in params {
+                    param.required_headers(headers);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /* Whether the type is or is built from a boolean type (_Bool and bool only exist since C99) */
+    pub fn uses_bool(&self) -> bool {
+        match self {
+            CType::_Bool | CType::Bool => true,
+            CType::Pointer(ctype) | CType::Array(ctype, _) => ctype.uses_bool(),
+            CType::Const(ctype) | CType::Volatile(ctype) | CType::Restrict(ctype) => ctype.uses_bool(),
+            CType::FunctionPointer { ret, params, .. } => ret.uses_bool() || params.iter().any(|param| param.uses_bool()),
+            _ => false,
+        }
+    }
+
+    /* Whether the type is or is built from a typedef name (after resolving, one that the program does not define) */
+    pub fn has_typedef_name(&self) -> bool {
+        match self {
+            CType::CustomTypedefType(_) => true,
+            CType::Pointer(ctype) | CType::Array(ctype, _) => ctype.has_typedef_name(),
+            CType::Const(ctype) | CType::Volatile(ctype) | CType::Restrict(ctype) => ctype.has_typedef_name(),
+            CType::FunctionPointer { ret, params, .. } => ret.has_typedef_name() || params.iter().any(|param| param.has_typedef_name()),
+            _ => false,
+        }
+    }
+
+    /* Whether a value of type `from` can initialize an object of this type without a cast (C11 6.5.16.1).
+       Typedef names in both types must already be resolved */
+    pub fn is_assignable_from(&self, from: &CType) -> bool {
+        let (to, from) = (self.unqualified(), from.unqualified());
+
+        if to.is_arithmetic() && from.is_arithmetic() {
+            return true;
+        }
+
+        match (to, from) {
+            // The pointee may gain qualifiers but not lose them, and void * converts to and from any object pointer:
+            (CType::Pointer(to_pointee), CType::Pointer(from_pointee) | CType::Array(from_pointee, _)) => {
+                let (to_qualifiers, to_pointee) = to_pointee.split_qualifiers();
+                let (from_qualifiers, from_pointee) = from_pointee.split_qualifiers();
+
+                from_qualifiers.iter().all(|qualifier| to_qualifiers.contains(qualifier))
+                    && (to_pointee == from_pointee || *to_pointee == CType::Void || *from_pointee == CType::Void)
+            }
+
+            _ => to == from,
+        }
+    }
+
+    /* Applies C's integer promotions (_Bool, char and short become int) */
+    pub fn promoted(&self) -> CType {
+        match self.unqualified() {
+            CType::_Bool | CType::Bool | CType::Char | CType::Short(_) => CType::Int,
+            CType::Int8 | CType::UInt8 | CType::Int16 | CType::UInt16 => CType::Int,
+            CType::Signed(ctype) | CType::Unsigned(ctype) if matches!(**ctype, CType::Char | CType::Short(_)) => CType::Int,
+            ctype => ctype.clone(),
+        }
+    }
+
+    /* Applies C's usual arithmetic conversions to find the common type of two operands (assumes LP64) */
+    pub fn usual_arithmetic_conversion(left: &CType, right: &CType) -> Option<CType> {
+        if !left.is_arithmetic() || !right.is_arithmetic() {
+            return None;
+        }
+
+        if left.is_floating() || right.is_floating() {
+            let rank = |ctype: &CType| ctype.floating_rank().unwrap_or(0);
+
+            return if rank(left) >= rank(right) {
+                Some(left.unqualified().clone())
+            } else {
+                Some(right.unqualified().clone())
+            };
+        }
+
+        let left = left.promoted();
+        let right = right.promoted();
+        let left_rank = left.integer_rank()?;
+        let right_rank = right.integer_rank()?;
+
+        if left == right {
+            return Some(left);
+        }
+
+        if left.is_unsigned() == right.is_unsigned() {
+            return Some(if left_rank >= right_rank { left } else { right });
+        }
+
+        let (unsigned, unsigned_rank, signed, signed_rank) = if left.is_unsigned() {
+            (left, left_rank, right, right_rank)
+        } else {
+            (right, right_rank, left, left_rank)
+        };
+
+        // int is 32 bits, long and long long are 64 bits:
+        let width = |rank: u8| if rank <= 4 { 32 } else { 64 };
+
+        if unsigned_rank >= signed_rank {
+            Some(unsigned)
+        } else if width(signed_rank) > width(unsigned_rank) {
+            Some(signed)
+        } else {
+            Some(signed.to_unsigned())
+        }
+    }
+
+    /* The unsigned integer type of the same rank (int64_t becomes uint64_t, signed long becomes unsigned long) */
+    pub fn to_unsigned(&self) -> CType {
+        match self.unqualified() {
+            ctype if ctype.is_unsigned() => ctype.clone(),
+            CType::Int8 => CType::UInt8,
+            CType::Int16 => CType::UInt16,
+            CType::Int32 => CType::UInt32,
+            CType::Int64 => CType::UInt64,
+            CType::IntPtr => CType::UIntPtr,
+            CType::PtrdiffT => CType::SizeT,
+            CType::CustomEnumType(_) => CType::Unsigned(Box::new(CType::Int)),
+            CType::Signed(ctype) => CType::Unsigned(ctype.clone()),
+            ctype => CType::Unsigned(Box::new(ctype.clone())),
+        }
+    }
+}
+
+impl CType {
+    /* Renders a declaration of `name` with this type, following C's inside-out declarator syntax
+     * (eg: `int *const p`, `char (*p)[]`, `int (*f)(int, char *)`). An empty name renders the type alone. */
+    pub fn declaration(&self, name: &str) -> String {
+        self.render_declarator(name.to_string(), true)
+    }
+
+    /* Renders only the declarator of `name`, for further names declared with the same specifier (the `*b` of `int *a, *b`) */
+    pub fn declarator(&self, name: &str) -> String {
+        self.render_declarator(name.to_string(), false)
+    }
+
+    fn render_declarator(&self, declarator: String, with_specifier: bool) -> String {
+        let (qualifiers, ctype) = self.split_qualifiers();
+
+        match ctype {
+            CType::Pointer(pointee) => {
+                let mut pointer = format!("*{}", qualifiers.join(" "));
+
+                if !qualifiers.is_empty() && !declarator.is_empty() {
+                    pointer.push(' ');
+                }
+
+                pointer.push_str(&declarator);
+
+                // The pointer has to bind before the array brackets or parameter list of its pointee:
+                if matches!(pointee.unqualified(), CType::Array(..)) {
+                    pointer = format!("({})", pointer);
+                }
+
+                pointee.render_declarator(pointer, with_specifier)
+            }
+
+            CType::FunctionPointer { ret, params, variadic } => {
+                let mut params = params.iter().map(|param| param.to_string()).collect::<Vec<_>>();
+
+                if *variadic {
+                    params.push("...".to_string());
+                } else if params.is_empty() {
+                    params.push("void".to_string());
+                }
+
+                let mut pointer = format!("*{}", qualifiers.join(" "));
+
+                if !qualifiers.is_empty() && !declarator.is_empty() {
+                    pointer.push(' ');
+                }
+
+                ret.render_declarator(format!("({}{})({})", pointer, declarator, params.join(", ")), with_specifier)
+            }
+
+            // Qualifiers of an array type apply to its elements:
+            CType::Array(element, size) => {
+                qualifiers.iter().rev().fold(*element.clone(), |element, qualifier| match *qualifier {
+                    "const" => CType::Const(Box::new(element)),
+                    "volatile" => CType::Volatile(Box::new(element)),
+                    _ => CType::Restrict(Box::new(element)),
+                }).render_declarator(format!("{}[{}]", declarator, size), with_specifier)
+            }
+
+            _ if !with_specifier => declarator,
+
+            _ => {
+                let mut code = qualifiers.iter().map(|qualifier| format!("{} ", qualifier)).collect::<String>();
+                code.push_str(&ctype.specifier());
+
+                if !declarator.is_empty() {
+                    code.push(' ');
+                    code.push_str(&declarator);
+                }
+
+                code
+            }
+        }
+    }
+
+    /* Splits the outermost `const`/`volatile`/`restrict` qualifiers from the type they qualify */
+    fn split_qualifiers(&self) -> (Vec<&'static str>, &CType) {
+        let mut qualifiers = Vec::new();
+        let mut ctype = self;
+
+        loop {
+            ctype = match ctype {
+                CType::Const(inner) => { qualifiers.push("const"); inner }
+                CType::Volatile(inner) => { qualifiers.push("volatile"); inner }
+                CType::Restrict(inner) => { qualifiers.push("restrict"); inner }
+                _ => return (qualifiers, ctype),
+            };
+        }
+    }
+
+    /* The type specifier of a non-derived type (int, unsigned long int, struct Point, ...) */
+    fn specifier(&self) -> String {
+        match self {
+            CType::Int => "int".to_string(),
+            CType::Char => "char".to_string(),
+            CType::Float => "float".to_string(),
+            CType::Double => "double".to_string(),
+            CType::Void => "void".to_string(),
+            CType::_Bool => "_Bool".to_string(),
+            CType::_Complex => "_Complex".to_string(),
+            CType::_Imaginary => "_Imaginary".to_string(),
+            CType::Int8 => "int8_t".to_string(),
+            CType::Int16 => "int16_t".to_string(),
+            CType::Int32 => "int32_t".to_string(),
+            CType::Int64 => "int64_t".to_string(),
+            CType::UInt8 => "uint8_t".to_string(),
+            CType::UInt16 => "uint16_t".to_string(),
+            CType::UInt32 => "uint32_t".to_string(),
+            CType::UInt64 => "uint64_t".to_string(),
+            CType::IntPtr => "intptr_t".to_string(),
+            CType::UIntPtr => "uintptr_t".to_string(),
+            CType::SizeT => "size_t".to_string(),
+            CType::PtrdiffT => "ptrdiff_t".to_string(),
+            CType::Bool => "bool".to_string(),
+            CType::Short(ctype) => format!("short {}", ctype),
+            CType::Long(ctype) => format!("long {}", ctype),
+            CType::Unsigned(ctype) => format!("unsigned {}", ctype),
+            CType::Signed(ctype) => format!("signed {}", ctype),
+            CType::CustomTypedefType(ctype) => ctype.to_string(),
+            CType::CustomStructType(ctype) => format!("struct {}", ctype),
+            CType::CustomEnumType(ctype) => format!("enum {}", ctype),
+            CType::CustomUnionType(ctype) => format!("union {}", ctype),
+            _ => self.declaration(""),
+        }
+    }
+}
+
+impl Display for CType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.declaration(""))
+    }
+}
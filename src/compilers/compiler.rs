@@ -28,25 +28,12 @@ pub struct JasmineBuilder {
 
 impl JasmineBuilder {
     pub fn new(compiler: Compilers) -> Self {
-        let mut os;
-
-        match OS {
-            "macos" => {
-                os = OperatingSystem::MacOS;
-            }
-
-            "linux" => {
-                os = OperatingSystem::Linux;
-            }
-
-            "windows" => {
-                os = OperatingSystem::Windows;
-            }
-
-            _ => {
-                os = OperatingSystem::Unknown;
-            }
-        }
+        let os = match OS {
+            "macos" => OperatingSystem::MacOS,
+            "linux" => OperatingSystem::Linux,
+            "windows" => OperatingSystem::Windows,
+            _ => OperatingSystem::Unknown,
+        };
 
         Self {
             files: Vec::new(),
@@ -60,7 +47,7 @@ impl JasmineBuilder {
         for library in file.headers.clone() {
             // Check if the header file/library is not a LibC file:
 
-            if library.lib_link == false {
+            if !library.lib_link {
                 self.headers.push(library);
             }
         }
@@ -76,7 +63,7 @@ impl JasmineBuilder {
             file_no += 1;
 
             if file_no == self.files.len() {
-                argfiles.push_str(format!("{}", file.get_file_path()).as_str());
+                argfiles.push_str(file.get_file_path().as_str());
             } else {
                 argfiles.push_str(format!("{},", file.get_file_path()).as_str());
             }
@@ -84,8 +71,8 @@ impl JasmineBuilder {
 
         match self.compiler {
             Compilers::Gcc => {
-                let output = Command::new("gcc")
-                    .args(&[
+                Command::new("gcc")
+                    .args([
                         argfiles.as_str(),      // Files
                         "-o",                   // Converts to executable
                         executable_name,        // Executable name
@@ -99,8 +86,8 @@ impl JasmineBuilder {
             }
 
             Compilers::Clang => {
-                let output = Command::new("clang")
-                    .args(&[
+                Command::new("clang")
+                    .args([
                         argfiles.as_str(),      // Files
                         "-o",                   // Converts to executable
                         executable_name,        // Executable name
@@ -1,10 +1,13 @@
 // ©2025 - BestJasmine - BestMat - All rights reserved.
 
-use crate::codegen::{CAssignmentStatement, CBlockStatement, CExpression, CFunctionCallStatement, CFunctionStatement, CLibrary, CNumberExpression, CReturnStatement, CStatement, CStringExpression, CVariableStatement, Codegen};
+use crate::codegen::{CAssignmentOperator, CAssignmentStatement, CBlockStatement, CExpression, CFunctionCallStatement, CFunctionStatement, CIdentifierExpression, CLibrary, CNumberExpression, CReturnStatement, CStatement, CStringExpression, CVariableStatement, Codegen};
 use crate::codegen::types::CType;
 use crate::compiler::{Compilers, JasmineBuilder};
 
+// The codegen library is exposed through this binary, so most of its API is unused here:
+#[allow(dead_code)]
 #[path="./codegen/codegen.rs"] mod codegen;
+#[allow(dead_code)] // Compilers and operating systems this binary does not pick
 #[path="./compilers/compiler.rs"] mod compiler;
 
 fn main() {
@@ -12,28 +15,36 @@ fn main() {
 
     codegen.add_include_statement(CLibrary::stdio());
 
-    codegen.add_function_statement(CFunctionStatement {
-        function_type: CType::Int,
-        function_name: "main".to_string(),
-        function_block: CBlockStatement {
-            block: vec![CStatement::VariableStatement(CVariableStatement {
-                var_type: CType::Int,
-                var_name: "x".to_string(),
-                var_value: CExpression::NumberExpression(CNumberExpression::new(21)),
-            }), CStatement::AssignmentStatement(CAssignmentStatement {
-                var_name: "x".to_string(),
-                var_value: CExpression::NumberExpression(CNumberExpression::new(27)),
-            }), CStatement::FunctionCallStatement(CFunctionCallStatement {
-                function: "printf".to_string(),
-                args: vec![CExpression::StringExpression(CStringExpression::new("Hello, world!\\n"))],
-            }), CStatement::ReturnStatement(CReturnStatement {
+    codegen.add_function_statement(CFunctionStatement::new(
+        CType::Int,
+        "main",
+        Vec::new(),
+        CBlockStatement {
+            block: vec![CStatement::VariableStatement(CVariableStatement::new(
+                CType::Int,
+                "x",
+                CExpression::NumberExpression(CNumberExpression::new(21)),
+            )), CStatement::AssignmentStatement(CAssignmentStatement {
+                target: CExpression::IdentifierExpression(CIdentifierExpression::new("x")),
+                operator: CAssignmentOperator::Assign,
+                value: CExpression::NumberExpression(CNumberExpression::new(27)),
+            }), CStatement::FunctionCallStatement(CFunctionCallStatement::new(
+                "printf",
+                vec![CExpression::StringExpression(CStringExpression::new("Hello, world!\n"))],
+            )), CStatement::ReturnStatement(CReturnStatement {
                 value: CExpression::NumberExpression(CNumberExpression::new(0)),
             })],
         },
-        function_args: Vec::new(),
-    });
+    ));
 
-    codegen.generate_code();
+    if let Err(error) = codegen.emit_to_file(codegen.c_file.get_file_path()) {
+        eprintln!("error: {}", error);
+        std::process::exit(1);
+    }
+
+    for warning in codegen.warnings() {
+        eprintln!("warning: {}", warning);
+    }
 
     let mut builder = JasmineBuilder::new(Compilers::Gcc);
     builder.add_file(codegen.get_c_file());